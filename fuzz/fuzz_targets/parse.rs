@@ -0,0 +1,41 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use dnsparse::{ArbitraryMessage, Message};
+
+fuzz_target!(|input: ArbitraryMessage| {
+  let mut input = input;
+
+  let _ = Message::parse_ref(input.as_bytes());
+  let _ = Message::parse_strict(input.clone().as_mut_bytes());
+  for message in Message::parse_stream(input.clone().as_mut_bytes()) {
+    let _ = message.map(|message| message.to_string());
+  }
+
+  let message = match Message::parse(input.as_mut_bytes()) {
+    Ok(message) => message,
+    Err(_) => return,
+  };
+
+  let _ = message.to_string();
+  let _ = message.extended_response_code();
+
+  for question in message.questions() {
+    let _ = question.name().to_string();
+    let _ = question.name().byte_len();
+    let _ = question.wire_len();
+  }
+
+  for (_, record) in message.records() {
+    let _ = record.name().to_string();
+    let _ = record.parse_rdata();
+    let _ = record.validate();
+
+    if let Some(opt) = record.as_opt() {
+      let _ = opt.options().count();
+      let _ = opt.cookie();
+      let _ = opt.ecs();
+    }
+  }
+});
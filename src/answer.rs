@@ -1,15 +1,26 @@
+use core::convert::TryFrom;
 use core::mem::size_of;
+use core::net::{Ipv4Addr, Ipv6Addr};
+use core::time::Duration;
 
 use crate::{Error, Name, QueryKind, QueryClass};
+use crate::name::push_bytes;
+use crate::rdata::rdata_names;
 
 /// A DNS answer.
-#[derive(Debug)]
+///
+/// Answers are read from a [`Message`](struct.Message.html) or constructed using
+/// [`Answer::builder`](#method.builder).
+#[derive(Debug, Clone)]
 pub struct Answer<'a> {
-  pub name: Name<'a>,
-  pub kind: QueryKind,
-  pub class: QueryClass,
-  pub ttl: u32,
-  pub rdata: &'a [u8],
+  pub(crate) name: Name<'a>,
+  pub(crate) kind: QueryKind,
+  pub(crate) class: QueryClass,
+  pub(crate) ttl: u32,
+  pub(crate) rdata: &'a [u8],
+  pub(crate) cache_flush: bool,
+  pub(crate) buf: &'a [u8],
+  pub(crate) rdata_i: usize,
 }
 
 fn read_ttl(buf: &[u8], i: &mut usize) -> Result<u32, Error> {
@@ -19,7 +30,7 @@ fn read_ttl(buf: &[u8], i: &mut usize) -> Result<u32, Error> {
     return Ok(ttl)
   }
 
-  Err(Error::MessageTooShort)
+  Err(Error::MessageTooShort { offset: *i })
 }
 
 fn read_rdata<'a>(buf: &'a [u8], i: &'_ mut usize) -> Result<&'a [u8], Error> {
@@ -33,22 +44,43 @@ fn read_rdata<'a>(buf: &'a [u8], i: &'_ mut usize) -> Result<&'a [u8], Error> {
     }
   }
 
-  Err(Error::MessageTooShort)
+  Err(Error::MessageTooShort { offset: *i })
 }
 
 impl<'a> Answer<'a> {
+  #[inline]
+  pub fn builder() -> AnswerBuilder<'a> {
+    AnswerBuilder::new()
+  }
+
   pub(crate) fn read(buf: &'a [u8], i: &'_ mut usize) -> Result<Self, Error> {
     let mut j = *i;
-    let answer = Self {
-      name:  Name::read(buf, &mut j)?,
-      kind:  QueryKind::read(buf, &mut j)?,
-      class: QueryClass::read(buf, &mut j)?,
-      ttl:   read_ttl(buf, &mut j)?,
-      rdata: read_rdata(buf, &mut j)?,
-    };
+    let name = Name::read(buf, &mut j)?;
+    let kind = QueryKind::read(buf, &mut j)?;
+    let class = QueryClass::read(buf, &mut j)?;
+    // The class of an `OPT` record contains the UDP payload size.
+    let (class, cache_flush) = if kind == QueryKind::OPT { (class, false) } else { class.split_mdns_flag() };
+    let ttl = read_ttl(buf, &mut j)?;
+    let rdata_i = j + size_of::<u16>();
+    let rdata = read_rdata(buf, &mut j)?;
     *i = j;
 
-    Ok(answer)
+    Ok(Self { name, kind, class, ttl, rdata, cache_flush, buf, rdata_i })
+  }
+
+  /// Read a name starting at `offset` within the RDATA, following pointers into the message.
+  ///
+  /// Returns the name together with the RDATA offset directly after it.
+  pub(crate) fn read_rdata_name(&self, offset: usize) -> Option<(Name<'a>, usize)> {
+    let mut i = self.rdata_i + offset;
+    let name = Name::read(self.buf, &mut i).ok()?;
+
+    let end = i - self.rdata_i;
+    if end > self.rdata.len() {
+      return None
+    }
+
+    Some((name, end))
   }
 
   pub fn name(&self) -> &Name<'a> {
@@ -67,13 +99,287 @@ impl<'a> Answer<'a> {
     self.ttl
   }
 
+  /// Whether this record replaces cached records, as used in multicast DNS.
+  ///
+  /// This is the top bit of the class, which is not included in [`class`](#method.class).
+  #[inline]
+  pub fn cache_flush(&self) -> bool {
+    self.cache_flush
+  }
+
+  #[inline]
+  pub fn ttl_duration(&self) -> Duration {
+    Duration::from_secs(u64::from(self.ttl))
+  }
+
   pub fn rdata(&self) -> &'a [u8] {
     self.rdata
   }
+
+  /// Returns the length of this record on the wire without name compression.
+  ///
+  /// This is an upper bound for the space needed to add it to a message.
+  pub fn wire_len(&self) -> usize {
+    self.name.byte_len() + size_of::<u16>() + size_of::<u16>() + size_of::<u32>() + size_of::<u16>() + self.rdata.len()
+  }
+
+  /// Write the wire form of this record into `out` without name compression.
+  ///
+  /// Names contained in the RDATA are written uncompressed, see
+  /// [`Message::append_answers_from`](struct.Message.html#method.append_answers_from).
+  /// Returns the number of bytes written.
+  pub fn write(&self, out: &mut [u8]) -> Result<usize, Error> {
+    let mut len = self.name.write(out)?;
+    push_bytes(out, &mut len, &self.kind.to_be_bytes())?;
+    push_bytes(out, &mut len, &self.class.to_be_bytes_with_mdns_flag(self.cache_flush))?;
+    push_bytes(out, &mut len, &self.ttl.to_be_bytes())?;
+
+    let rdata_len_i = len;
+    push_bytes(out, &mut len, &[0, 0])?;
+    self.write_rdata(|bytes| push_bytes(out, &mut len, bytes))?;
+
+    let rdata_len = len - rdata_len_i - size_of::<u16>();
+    let rdata_len_bytes = u16::try_from(rdata_len).map_err(|_| Error::MessageTooLong)?.to_be_bytes();
+    out[rdata_len_i..(rdata_len_i + size_of::<u16>())].copy_from_slice(&rdata_len_bytes);
+
+    Ok(len)
+  }
+
+  /// Write the RDATA using `push`, writing all names it contains uncompressed.
+  ///
+  /// Returns an error if a name cannot be read. RDATA of other kinds, or which is
+  /// too malformed to locate its names, is written as is.
+  pub(crate) fn write_rdata<F>(&self, mut push: F) -> Result<(), Error>
+  where
+    F: FnMut(&[u8]) -> Result<(), Error>,
+  {
+    let (mut i, count) = match rdata_names(self.kind, self.rdata) {
+      Some(names) => names,
+      None => return push(self.rdata),
+    };
+
+    push(self.rdata.get(..i).ok_or(Error::InvalidRdata { offset: self.rdata_i })?)?;
+
+    for _ in 0..count {
+      let (name, end) = self.read_rdata_name(i).ok_or(Error::InvalidRdata { offset: self.rdata_i + i })?;
+
+      for label in name.labels() {
+        push(&[label.len() as u8])?;
+        push(label.as_bytes())?;
+      }
+      push(&[0])?;
+
+      i = end;
+    }
+
+    push(&self.rdata[i..])
+  }
+
+  /// Compare two records semantically, ignoring the TTL.
+  ///
+  /// Names contained in the RDATA are compared by their labels.
+  pub(crate) fn eq_ignoring_ttl(&self, other: &Answer<'_>) -> bool {
+    if self.name != other.name || self.kind != other.kind || self.class != other.class || self.cache_flush != other.cache_flush {
+      return false
+    }
+
+    self.rdata_eq(other)
+  }
+
+  /// Compare the RDATA of two records of the same kind, following pointers in names it contains.
+  ///
+  /// RDATA whose names cannot be read is compared as is.
+  fn rdata_eq(&self, other: &Answer<'_>) -> bool {
+    let (mut i, count) = match rdata_names(self.kind, self.rdata) {
+      Some(names) if Some(names) == rdata_names(other.kind, other.rdata) => names,
+      _ => return self.rdata == other.rdata,
+    };
+
+    if self.rdata.get(..i) != other.rdata.get(..i) {
+      return false
+    }
+
+    let mut j = i;
+    for _ in 0..count {
+      match (self.read_rdata_name(i), other.read_rdata_name(j)) {
+        (Some((name, end)), Some((other_name, other_end))) if name == other_name => {
+          i = end;
+          j = other_end;
+        },
+        _ => return self.rdata == other.rdata,
+      }
+    }
+
+    self.rdata[i..] == other.rdata[j..]
+  }
+
+  /// Check that the RDATA length matches the record kind for kinds with a fixed length.
+  pub fn validate(&self) -> Result<(), Error> {
+    let expected_len = match (self.kind, self.class) {
+      (QueryKind::A, QueryClass::IN) => 4,
+      (QueryKind::AAAA, QueryClass::IN) => 16,
+      _ => return Ok(()),
+    };
+
+    if self.rdata.len() != expected_len {
+      return Err(Error::InvalidRdata { offset: self.rdata_i })
+    }
+
+    Ok(())
+  }
+
+  pub(crate) fn rdata_u16(&self, offset: usize) -> Option<u16> {
+    let bytes = self.rdata.get(offset..(offset + size_of::<u16>()))?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+  }
+
+  pub(crate) fn rdata_u32(&self, offset: usize) -> Option<u32> {
+    let bytes = self.rdata.get(offset..(offset + size_of::<u32>()))?;
+    Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+  }
+
+  /// Returns the domain name contained in a `CNAME`, `NS` or `PTR` record.
+  pub fn rdata_name(&self) -> Option<Name<'a>> {
+    match self.kind {
+      QueryKind::CNAME | QueryKind::NS | QueryKind::PTR => {
+        let (name, end) = self.read_rdata_name(0)?;
+
+        if end != self.rdata.len() {
+          return None
+        }
+
+        Some(name)
+      },
+      _ => None,
+    }
+  }
+
+  /// Returns the preference and exchange name contained in an `MX` record.
+  pub fn as_mx(&self) -> Option<(u16, Name<'a>)> {
+    if self.kind != QueryKind::MX {
+      return None
+    }
+
+    let preference = self.rdata_u16(0)?;
+    let (exchange, end) = self.read_rdata_name(size_of::<u16>())?;
+
+    if end != self.rdata.len() {
+      return None
+    }
+
+    Some((preference, exchange))
+  }
+
+  /// Returns the redirection target contained in a `DNAME` record.
+  ///
+  /// According to [RFC 6672](https://tools.ietf.org/rfc/rfc6672#section-2.1).
+  pub fn as_dname(&self) -> Option<Name<'a>> {
+    if self.kind != QueryKind::DNAME {
+      return None
+    }
+
+    let (target, end) = self.read_rdata_name(0)?;
+
+    if end != self.rdata.len() {
+      return None
+    }
+
+    Some(target)
+  }
+
+  /// Returns the address contained in an `A` record.
+  pub fn as_ipv4(&self) -> Option<Ipv4Addr> {
+    if self.kind != QueryKind::A {
+      return None
+    }
+
+    let octets = <[u8; 4]>::try_from(self.rdata).ok()?;
+    Some(Ipv4Addr::from(octets))
+  }
+
+  /// Returns the address contained in an `AAAA` record.
+  pub fn as_ipv6(&self) -> Option<Ipv6Addr> {
+    if self.kind != QueryKind::AAAA {
+      return None
+    }
+
+    let octets = <[u8; 16]>::try_from(self.rdata).ok()?;
+    Some(Ipv6Addr::from(octets))
+  }
 }
 
-/// Iterator over [`Answer`](struct.Answer.html)s contained in a [`Message`](struct.Message.html).
+/// Builder for [`Answer`](struct.Answer.html).
+///
+/// The name defaults to the root name, the class to `IN` and the TTL to 0.
 #[derive(Debug)]
+pub struct AnswerBuilder<'a>(Answer<'a>);
+
+impl Default for AnswerBuilder<'_> {
+  fn default() -> Self {
+    Self(Answer {
+      name: Name::root(),
+      kind: QueryKind::A,
+      class: QueryClass::IN,
+      ttl: 0,
+      rdata: &[],
+      cache_flush: false,
+      buf: &[],
+      rdata_i: 0,
+    })
+  }
+}
+
+impl<'a> AnswerBuilder<'a> {
+  #[inline]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn name(mut self, name: Name<'a>) -> Self {
+    self.0.name = name;
+    self
+  }
+
+  pub fn kind(mut self, kind: QueryKind) -> Self {
+    self.0.kind = kind;
+    self
+  }
+
+  pub fn class(mut self, class: QueryClass) -> Self {
+    self.0.class = class;
+    self
+  }
+
+  pub fn ttl(mut self, ttl: u32) -> Self {
+    self.0.ttl = ttl;
+    self
+  }
+
+  pub fn cache_flush(mut self, cache_flush: bool) -> Self {
+    self.0.cache_flush = cache_flush;
+    self
+  }
+
+  /// Set the TTL in whole seconds, saturating at `u32::MAX`.
+  pub fn ttl_duration(self, ttl: Duration) -> Self {
+    self.ttl(u32::try_from(ttl.as_secs()).unwrap_or(u32::MAX))
+  }
+
+  /// Set the RDATA. Names contained in it must not be compressed.
+  pub fn rdata(mut self, rdata: &'a [u8]) -> Self {
+    self.0.rdata = rdata;
+    self.0.buf = rdata;
+    self.0.rdata_i = 0;
+    self
+  }
+
+  pub fn build(self) -> Answer<'a> {
+    self.0
+  }
+}
+
+/// Iterator over [`Answer`](struct.Answer.html)s contained in a [`Message`](struct.Message.html).
+#[derive(Debug, Clone)]
 pub struct Answers<'a> {
   pub(crate) answer_count: usize,
   pub(crate) current_answer: usize,
@@ -97,4 +403,61 @@ impl<'a> Iterator for Answers<'a> {
 
     Some(answer)
   }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let remaining = self.answer_count.saturating_sub(self.current_answer);
+    (remaining, Some(remaining))
+  }
+}
+
+impl ExactSizeIterator for Answers<'_> {}
+
+/// The section of a [`Message`](struct.Message.html) containing a resource record.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Section {
+  Answer,
+  Authority,
+  Additional,
 }
+
+/// Iterator over all resource records contained in a [`Message`](struct.Message.html),
+/// together with their [`Section`](enum.Section.html).
+#[derive(Debug)]
+pub struct Records<'a> {
+  pub(crate) answer_count: usize,
+  pub(crate) name_server_count: usize,
+  pub(crate) additional_records_count: usize,
+  pub(crate) current_record: usize,
+  pub(crate) buf: &'a [u8],
+  pub(crate) buf_i: usize,
+}
+
+impl<'a> Iterator for Records<'a> {
+  type Item = (Section, Answer<'a>);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let section = if self.current_record < self.answer_count {
+      Section::Answer
+    } else if self.current_record < self.answer_count + self.name_server_count {
+      Section::Authority
+    } else if self.current_record < self.answer_count + self.name_server_count + self.additional_records_count {
+      Section::Additional
+    } else {
+      return None
+    };
+
+    let record = Answer::read(self.buf, &mut self.buf_i).ok()?;
+
+    self.current_record += 1;
+
+    Some((section, record))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let total = self.answer_count + self.name_server_count + self.additional_records_count;
+    let remaining = total.saturating_sub(self.current_record);
+    (remaining, Some(remaining))
+  }
+}
+
+impl ExactSizeIterator for Records<'_> {}
@@ -1,28 +1,67 @@
 use core::fmt;
 
 /// A DNS parsing error.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Error {
-  /// Message ended unexpectedly.
-  MessageTooShort,
+  /// Message ended unexpectedly while reading the field at `offset`.
+  MessageTooShort { offset: usize },
   /// Message exceeds maximum length.
   MessageTooLong,
-  /// Wrong label pointer.
-  Pointer,
+  /// Wrong label pointer at `offset`.
+  Pointer { offset: usize },
   /// Name exceeded maximum length.
   NameTooLong,
+  /// Label exceeded maximum length.
+  LabelTooLong,
+  /// Name contains an empty label.
+  EmptyLabel,
+  /// Label at `offset` has a reserved label type or contains invalid characters.
+  InvalidLabel { offset: usize },
+  /// RDATA starting at `offset` has the wrong length for its record kind.
+  InvalidRdata { offset: usize },
+  /// EDNS option is invalid.
+  InvalidOption,
+  /// Message contains unexpected bytes starting at `offset`.
+  TrailingBytes { offset: usize },
+  /// Output buffer is too small.
+  BufferTooSmall,
+  /// Unknown query kind mnemonic.
+  UnknownQueryKind,
+  /// Unknown query class mnemonic.
+  UnknownQueryClass,
 }
 
 impl fmt::Display for Error {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-    f.write_str(match self {
-      Error::MessageTooShort => "message too short",
-      Error::MessageTooLong => "message too long",
-      Error::Pointer => "invalid pointer",
-      Error::NameTooLong => "name too long",
-    })
+    match self {
+      Error::MessageTooShort { offset } => write!(f, "message too short at offset {}", offset),
+      Error::MessageTooLong => f.write_str("message too long"),
+      Error::Pointer { offset } => write!(f, "invalid pointer at offset {}", offset),
+      Error::NameTooLong => f.write_str("name too long"),
+      Error::LabelTooLong => f.write_str("label too long"),
+      Error::EmptyLabel => f.write_str("empty label"),
+      Error::InvalidLabel { offset } => write!(f, "invalid label at offset {}", offset),
+      Error::InvalidRdata { offset } => write!(f, "invalid RDATA at offset {}", offset),
+      Error::InvalidOption => f.write_str("invalid option"),
+      Error::TrailingBytes { offset } => write!(f, "trailing bytes at offset {}", offset),
+      Error::BufferTooSmall => f.write_str("buffer too small"),
+      Error::UnknownQueryKind => f.write_str("unknown query kind"),
+      Error::UnknownQueryClass => f.write_str("unknown query class"),
+    }
   }
 }
 
 #[cfg(feature = "std")]
 impl std::error::Error for Error {}
+
+#[cfg(feature = "std")]
+impl From<Error> for std::io::Error {
+  fn from(err: Error) -> Self {
+    let kind = match err {
+      Error::BufferTooSmall | Error::UnknownQueryKind | Error::UnknownQueryClass => std::io::ErrorKind::InvalidInput,
+      _ => std::io::ErrorKind::InvalidData,
+    };
+
+    std::io::Error::new(kind, err)
+  }
+}
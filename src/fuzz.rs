@@ -0,0 +1,48 @@
+use core::mem::size_of;
+
+use arbitrary::{Arbitrary, Unstructured};
+
+use crate::{Message, MessageBuffer};
+
+/// A buffer of arbitrary bytes to parse as a [`Message`](struct.Message.html), used for fuzzing.
+#[derive(Debug, Clone)]
+pub struct ArbitraryMessage {
+  buf: MessageBuffer,
+  len: usize,
+}
+
+impl ArbitraryMessage {
+  #[inline]
+  pub fn as_bytes(&self) -> &[u8] {
+    &self.buf[..self.len]
+  }
+
+  #[inline]
+  pub fn as_mut_bytes(&mut self) -> &mut [u8] {
+    &mut self.buf[..self.len]
+  }
+
+  fn from_bytes(bytes: &[u8]) -> Self {
+    let len = bytes.len().min(Message::MAX_UDP_PAYLOAD);
+
+    let mut buf = Message::BUFFER;
+    buf[..len].copy_from_slice(&bytes[..len]);
+
+    Self { buf, len }
+  }
+}
+
+impl<'a> Arbitrary<'a> for ArbitraryMessage {
+  fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+    let len = u.int_in_range(0..=Message::MAX_UDP_PAYLOAD)?;
+    Ok(Self::from_bytes(u.bytes(len.min(u.len()))?))
+  }
+
+  fn arbitrary_take_rest(u: Unstructured<'a>) -> arbitrary::Result<Self> {
+    Ok(Self::from_bytes(u.take_rest()))
+  }
+
+  fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+    (0, Some(size_of::<usize>() + Message::MAX_UDP_PAYLOAD))
+  }
+}
@@ -1,7 +1,11 @@
 use core::fmt;
+#[cfg(target_has_atomic = "16")]
+use core::sync::atomic::{AtomicU16, Ordering};
 
 /// A DNS header.
-#[derive(Clone)]
+#[derive(Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "HeaderRepr", into = "HeaderRepr"))]
 #[repr(C)]
 pub struct Header {
   id: [u8; 2],
@@ -14,6 +18,7 @@ pub struct Header {
 
 /// The kind of a DNS header.
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HeaderKind {
   Query,
   Response,
@@ -21,6 +26,7 @@ pub enum HeaderKind {
 
 /// A DNS opcode.
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum OpCode {
   Query,
   InverseQuery,
@@ -32,6 +38,7 @@ pub enum OpCode {
 
 /// A DNS response code.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ResponseCode {
   NoError,
   FormatError,
@@ -53,6 +60,19 @@ pub enum ResponseCode {
   Reserved(u16),
 }
 
+impl fmt::Display for OpCode {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      OpCode::Query => "QUERY",
+      OpCode::InverseQuery => "IQUERY",
+      OpCode::Status => "STATUS",
+      OpCode::Notify => "NOTIFY",
+      OpCode::Update => "UPDATE",
+      OpCode::Reserved(n) => return write!(f, "RESERVED{}", n),
+    })
+  }
+}
+
 impl From<ResponseCode> for u16 {
   fn from(r: ResponseCode) -> Self {
     match r {
@@ -78,6 +98,31 @@ impl From<ResponseCode> for u16 {
   }
 }
 
+impl fmt::Display for ResponseCode {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      ResponseCode::NoError => "NOERROR",
+      ResponseCode::FormatError => "FORMERR",
+      ResponseCode::ServerFailure => "SERVFAIL",
+      ResponseCode::NonExistentDomain => "NXDOMAIN",
+      ResponseCode::NotImplemented => "NOTIMP",
+      ResponseCode::Refused => "REFUSED",
+      ResponseCode::ExistentDomain => "YXDOMAIN",
+      ResponseCode::ExistentRrSet => "YXRRSET",
+      ResponseCode::NonExistentRrSet => "NXRRSET",
+      ResponseCode::NotAuthoritative => "NOTAUTH",
+      ResponseCode::NotZone => "NOTZONE",
+      ResponseCode::BadOptVersionOrBadSignature => "BADVERS",
+      ResponseCode::BadKey => "BADKEY",
+      ResponseCode::BadTime => "BADTIME",
+      ResponseCode::BadMode => "BADMODE",
+      ResponseCode::BadName => "BADNAME",
+      ResponseCode::BadAlg => "BADALG",
+      ResponseCode::Reserved(n) => return write!(f, "RCODE{}", n),
+    })
+  }
+}
+
 impl From<u16> for ResponseCode {
   fn from(n: u16) -> Self {
     match n {
@@ -123,6 +168,20 @@ impl fmt::Debug for Header {
 }
 
 impl Header {
+  /// Create a header with all fields set to zero.
+  #[inline]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Returns the wire form of this header.
+  #[inline]
+  pub fn as_bytes(&self) -> &[u8; 12] {
+    // `Header` is `#[repr(C)]` and only consists of byte arrays, so it has
+    // a size of 12 bytes, an alignment of 1 and no padding.
+    unsafe { &*(self as *const Self as *const [u8; 12]) }
+  }
+
   #[inline]
   pub fn id(&self) -> u16 {
     u16::from_be_bytes(self.id)
@@ -170,7 +229,7 @@ impl Header {
       OpCode::Status => 2,
       OpCode::Notify => 4,
       OpCode::Update => 5,
-      OpCode::Reserved(n) => n,
+      OpCode::Reserved(n) => n & 0b1111,
     } << 3);
   }
 
@@ -184,6 +243,15 @@ impl Header {
     (self.flags[0] & 0b00000010) != 0
   }
 
+  #[inline]
+  pub fn set_truncated(&mut self, truncated: bool) {
+    if truncated {
+      self.flags[0] |= 0b00000010;
+    } else {
+      self.flags[0] &= 0b11111101;
+    }
+  }
+
   #[inline]
   pub fn recursion_desired(&self) -> bool {
     (self.flags[0] & 0b00000001) != 0
@@ -217,9 +285,15 @@ impl Header {
     u16::from(self.flags[1] & 0b00001111).into()
   }
 
+  /// Set the response code.
+  ///
+  /// Only response codes up to 15 fit into the header. The upper 8 bits of extended response
+  /// codes have to be set in the extended RCODE field of the `OPT` record instead, see
+  /// [`extended_response_code`](struct.MessageRef.html#method.extended_response_code).
   #[inline]
   pub fn set_response_code(&mut self, response_code: ResponseCode) {
-    self.flags[1] = (self.flags[1] & 0b11110000) | u16::from(response_code) as u8;
+    debug_assert!(u16::from(response_code) <= 0b1111, "extended response code must be set in the OPT record");
+    self.flags[1] = (self.flags[1] & 0b11110000) | (u16::from(response_code) & 0b1111) as u8;
   }
 
   #[inline]
@@ -278,26 +352,95 @@ impl Header {
     self.additional_records_count = additional_records_count.to_be_bytes();
   }
 
+  pub(crate) fn eq_ignoring_id(&self, other: &Header) -> bool {
+    self.flags == other.flags &&
+      self.question_count == other.question_count &&
+      self.answer_count == other.answer_count &&
+      self.name_server_count == other.name_server_count &&
+      self.additional_records_count == other.additional_records_count
+  }
+
   #[inline]
   pub fn builder() -> HeaderBuilder {
     HeaderBuilder::new()
   }
 }
 
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "Header")]
+struct HeaderRepr {
+  id: u16,
+  kind: HeaderKind,
+  opcode: OpCode,
+  authoritative_answer: bool,
+  truncated: bool,
+  recursion_desired: bool,
+  recursion_available: bool,
+  response_code: ResponseCode,
+  question_count: u16,
+  answer_count: u16,
+  name_server_count: u16,
+  additional_records_count: u16,
+}
+
+#[cfg(feature = "serde")]
+impl From<Header> for HeaderRepr {
+  fn from(header: Header) -> Self {
+    Self {
+      id: header.id(),
+      kind: header.kind(),
+      opcode: header.opcode(),
+      authoritative_answer: header.authoritative_answer(),
+      truncated: header.truncated(),
+      recursion_desired: header.recursion_desired(),
+      recursion_available: header.recursion_available(),
+      response_code: header.response_code(),
+      question_count: header.question_count(),
+      answer_count: header.answer_count(),
+      name_server_count: header.name_server_count(),
+      additional_records_count: header.additional_records_count(),
+    }
+  }
+}
+
+#[cfg(feature = "serde")]
+impl From<HeaderRepr> for Header {
+  fn from(repr: HeaderRepr) -> Self {
+    let mut header = HeaderBuilder::new()
+      .id(repr.id)
+      .kind(repr.kind)
+      .recursion_desired(repr.recursion_desired)
+      .recursion_available(repr.recursion_available)
+      .response_code(ResponseCode::from(u16::from(repr.response_code) & 0b1111))
+      .build();
+
+    header.set_opcode(repr.opcode);
+
+    if repr.authoritative_answer {
+      header.flags[0] |= 0b00000100;
+    }
+
+    if repr.truncated {
+      header.flags[0] |= 0b00000010;
+    }
+
+    header.question_count = repr.question_count.to_be_bytes();
+    header.answer_count = repr.answer_count.to_be_bytes();
+    header.name_server_count = repr.name_server_count.to_be_bytes();
+    header.additional_records_count = repr.additional_records_count.to_be_bytes();
+
+    header
+  }
+}
+
 /// Builder for [`Header`](struct.Header.html).
 #[derive(Debug)]
 pub struct HeaderBuilder(Header);
 
 impl Default for HeaderBuilder {
   fn default() -> Self {
-    Self(Header {
-      id: [0, 0],
-      flags: [0, 0],
-      question_count: [0, 0],
-      answer_count: [0, 0],
-      name_server_count: [0, 0],
-      additional_records_count: [0, 0],
-    })
+    Self(Header::new())
   }
 }
 
@@ -307,11 +450,42 @@ impl HeaderBuilder {
     Self::default()
   }
 
+  /// Set the ID.
+  ///
+  /// The default ID of zero is predictable. Clients should use an unpredictable ID
+  /// to make cache poisoning harder, e.g. using
+  #[cfg_attr(feature = "getrandom", doc = "[`random_id`](#method.random_id) or")]
+  /// [`random_id_with`](#method.random_id_with).
   pub fn id(mut self, id: u16) -> Self {
     self.0.set_id(id);
     self
   }
 
+  /// Set a random ID using the system's random number generator.
+  #[cfg(feature = "getrandom")]
+  pub fn random_id(self) -> Result<Self, getrandom::Error> {
+    let mut id = [0; 2];
+    getrandom::getrandom(&mut id)?;
+    Ok(self.id(u16::from_be_bytes(id)))
+  }
+
+  /// Set a random ID using the given random number generator.
+  pub fn random_id_with(self, mut rng: impl FnMut() -> u16) -> Self {
+    let id = rng();
+    self.id(id)
+  }
+
+  /// Set the next ID from `counter` and increment it, wrapping around after `u16::MAX`.
+  ///
+  /// Sequential IDs are predictable and make cache poisoning easy, so they should
+  /// only be used on trusted links. Prefer a random ID otherwise, see
+  #[cfg_attr(feature = "getrandom", doc = "[`random_id`](#method.random_id) and")]
+  /// [`random_id_with`](#method.random_id_with).
+  #[cfg(target_has_atomic = "16")]
+  pub fn sequential_id(self, counter: &AtomicU16) -> Self {
+    self.id(counter.fetch_add(1, Ordering::Relaxed))
+  }
+
   pub fn kind(mut self, kind: HeaderKind) -> Self {
     self.0.set_kind(kind);
     self
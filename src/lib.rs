@@ -5,11 +5,17 @@
 //!
 //! Implemented according to [RFC 1035](https://tools.ietf.org/rfc/rfc1035).
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 mod error;
 pub use error::Error;
 
 mod message;
-pub use message::{Message, MessageBuilder, MessageBuffer};
+pub use message::{Message, MessageBuilder, MessageBuffer, MessageRef, MessageStream, StrictOptions};
+
+mod write;
+pub use write::Write;
 
 mod header;
 pub use header::{Header, HeaderKind, ResponseCode, OpCode};
@@ -26,5 +32,23 @@ pub use question::{Question, Questions};
 mod name;
 pub use name::Name;
 
+#[cfg(feature = "heapless")]
+mod name_buf;
+#[cfg(feature = "heapless")]
+pub use name_buf::NameBuf;
+
 mod answer;
-pub use answer::{Answer, Answers};
+pub use answer::{Answer, AnswerBuilder, Answers, Records, Section};
+
+mod rdata;
+pub use rdata::{Caa, Cookie, Ds, EcsOption, EdnsOptions, Loc, Naptr, Opt, OptBuilder, Rdata, Rrsig, Serial, Soa, Srv, Sshfp, SvcParams, Svcb, Tlsa, TxtStrings};
+
+#[cfg(feature = "arbitrary")]
+mod fuzz;
+#[cfg(feature = "arbitrary")]
+pub use fuzz::ArbitraryMessage;
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+mod owned;
+#[cfg(all(feature = "serde", feature = "alloc"))]
+pub use owned::{OwnedAnswer, OwnedMessage, OwnedQuestion};
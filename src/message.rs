@@ -1,11 +1,19 @@
+use core::convert::TryFrom;
 use core::ops::Deref;
 use core::mem::{size_of};
 use core::fmt;
 
-use crate::{Error, Answer, Answers, Header, Question, Questions, QueryKind, QueryClass, Name};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 
-const HEADER_SIZE: usize = size_of::<Header>();
+use crate::{Error, Answer, Answers, Header, HeaderKind, ResponseCode, Question, Questions, QueryKind, QueryClass, Name, Rdata, Records, Section, Write};
+use crate::name::{pointer, skip_name, MAX_NAME_LEN};
+use crate::rdata::rdata_names;
+
+pub(crate) const HEADER_SIZE: usize = size_of::<Header>();
 const MAX_MESSAGE_SIZE: usize = 512 - HEADER_SIZE;
+/// Messages sent over TCP are prefixed with a 16-bit length.
+const MAX_TCP_MESSAGE_SIZE: usize = u16::MAX as usize;
 
 /// Helper type for constructing a buffer with the maximum UDP message size.
 pub type MessageBuffer = [u8; HEADER_SIZE + MAX_MESSAGE_SIZE];
@@ -19,64 +27,238 @@ pub struct Message<'a> {
 
 /// Builder for [`Message`](struct.Message.html).
 #[derive(Debug)]
-pub struct MessageBuilder<'a> {
-  buf: &'a mut [u8],
-  len: usize,
-}
+pub struct MessageBuilder<'a>(Message<'a>);
 
 impl<'a> MessageBuilder<'a> {
+  /// Set the header, keeping the counts of records added so far.
   pub fn header(mut self, header: Header) -> Self {
-    *self.header_mut() = header;
+    let message_header = self.0.header_mut();
+    let question_count = message_header.question_count();
+    let answer_count = message_header.answer_count();
+    let name_server_count = message_header.name_server_count();
+    let additional_records_count = message_header.additional_records_count();
 
-    self.len = HEADER_SIZE;
+    *message_header = header;
+
+    unsafe {
+      message_header.set_question_count(question_count);
+      message_header.set_answer_count(answer_count);
+      message_header.set_name_server_count(name_server_count);
+      message_header.set_additional_records_count(additional_records_count);
+    }
 
     self
   }
 
-  fn header_mut(&mut self) -> &mut Header {
-    unsafe { &mut *(self.buf[..HEADER_SIZE].as_mut_ptr() as *mut _ as *mut Header) }
+  /// Append a question.
+  pub fn question(mut self, question: &Question<'_>) -> Result<Self, Error> {
+    self.0.add_question(question)?;
+    Ok(self)
+  }
+
+  /// Append an answer. Answers are always placed after all questions.
+  pub fn answer(mut self, answer: &Answer<'_>) -> Result<Self, Error> {
+    self.0.add_answer(answer)?;
+    Ok(self)
+  }
+
+  /// Append a record to the authority section.
+  pub fn name_server(mut self, name_server: &Answer<'_>) -> Result<Self, Error> {
+    self.0.add_name_server(name_server)?;
+    Ok(self)
+  }
+
+  /// Append a record to the additional section.
+  pub fn additional_record(mut self, additional_record: &Answer<'_>) -> Result<Self, Error> {
+    self.0.add_additional_record(additional_record)?;
+    Ok(self)
   }
 
   pub fn build(self) -> Message<'a> {
-    let Self { buf, len } = self;
-    Message { buf, len }
+    self.0
+  }
+}
+
+/// Additional checks for [`Message::parse_strict_with`](struct.Message.html#method.parse_strict_with).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StrictOptions {
+  uncompressed_question_names: bool,
+}
+
+impl StrictOptions {
+  #[inline]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Reject question names which are compressed using a pointer.
+  #[inline]
+  pub fn uncompressed_question_names(mut self, uncompressed_question_names: bool) -> Self {
+    self.uncompressed_question_names = uncompressed_question_names;
+    self
   }
 }
 
 impl<'a> Message<'a> {
-  pub const BUFFER: [u8; 512] = [0; 512];
+  /// Size of the message header in bytes.
+  pub const HEADER_SIZE: usize = HEADER_SIZE;
+  /// Maximum size of a message sent over UDP without EDNS, including the header.
+  pub const MAX_UDP_PAYLOAD: usize = HEADER_SIZE + MAX_MESSAGE_SIZE;
+
+  pub const BUFFER: MessageBuffer = [0; Self::MAX_UDP_PAYLOAD];
 
   pub fn builder(buf: &'a mut [u8]) -> MessageBuilder<'a> {
     for b in &mut buf[..HEADER_SIZE] {
       *b = 0;
     }
 
-    MessageBuilder { buf, len: HEADER_SIZE }
+    MessageBuilder(Message { buf, len: HEADER_SIZE })
+  }
+
+  /// Create a standard query for `name` in `buf`.
+  ///
+  /// The query has an ID of 0, the recursion desired flag set and a single question of class `IN`.
+  pub fn query(name: &str, kind: QueryKind, buf: &'a mut [u8]) -> Result<Message<'a>, Error> {
+    let mut name_buf = [0; MAX_NAME_LEN];
+    let name = Name::parse_str(name, &mut name_buf)?;
+
+    let header = Header::builder()
+      .recursion_desired(true)
+      .build();
+
+    Ok(Self::builder(buf).header(header).question(&Question::new(name, kind, QueryClass::IN))?.build())
+  }
+
+  /// Start a response to `query` in `buf`.
+  ///
+  /// The response has the same ID, opcode and questions as the query
+  /// and copies its recursion desired flag.
+  pub fn respond(query: &MessageRef, buf: &'a mut [u8]) -> Result<Message<'a>, Error> {
+    let query_header = query.header();
+
+    let header = Header::builder()
+      .id(query_header.id())
+      .kind(HeaderKind::Response)
+      .recursion_desired(query_header.recursion_desired())
+      .build();
+
+    let mut response = Self::builder(buf).header(header).build();
+    response.header_mut().set_opcode(query_header.opcode());
+
+    for question in query.questions() {
+      response.add_question(&question)?;
+    }
+
+    Ok(response)
   }
 
   pub fn parse(buffer: &'a mut [u8]) -> Result<Message<'a>, Error> {
-    if buffer.len() < HEADER_SIZE {
-      return Err(Error::MessageTooShort)
-    } else if buffer.len() > HEADER_SIZE + MAX_MESSAGE_SIZE {
-      return Err(Error::MessageTooLong)
+    Self::parse_with_limit(buffer, HEADER_SIZE + MAX_MESSAGE_SIZE)
+  }
+
+  /// Parse a message from a buffer of up to `max_len` bytes instead of the 512 bytes
+  /// allowed for plain UDP, e.g. the UDP payload size negotiated using EDNS.
+  pub fn parse_with_limit(buffer: &'a mut [u8], max_len: usize) -> Result<Message<'a>, Error> {
+    let len = MessageRef::parse_len(buffer, max_len)?;
+    Ok(Self { buf: buffer, len })
+  }
+
+  /// Parse a message, additionally rejecting anything not strictly conforming to the specification.
+  ///
+  /// All names must only consist of letters, digits, hyphens and underscores,
+  /// `A` and `AAAA` records must have RDATA of the correct length
+  /// and `buffer` must not contain any bytes after the last record.
+  pub fn parse_strict(buffer: &'a mut [u8]) -> Result<Message<'a>, Error> {
+    Self::parse_strict_with(buffer, StrictOptions::default())
+  }
+
+  /// Parse a message like [`parse_strict`](#method.parse_strict), additionally
+  /// applying the checks enabled in `options`.
+  pub fn parse_strict_with(buffer: &'a mut [u8], options: StrictOptions) -> Result<Message<'a>, Error> {
+    let len = MessageRef::parse_len(buffer, HEADER_SIZE + MAX_MESSAGE_SIZE)?;
+    if len != buffer.len() {
+      return Err(Error::TrailingBytes { offset: len })
     }
 
-    let len = buffer.len();
-    let mut frame = Self { buf: buffer, len };
+    let message = MessageRef::from_bytes(&buffer[..len]);
+    message.validate()?;
 
-    let mut i = HEADER_SIZE;
+    if options.uncompressed_question_names {
+      for question in message.questions() {
+        if let Some(offset) = question.name().pointer_offset() {
+          return Err(Error::Pointer { offset })
+        }
+      }
+    }
+
+    Ok(Self { buf: buffer, len })
+  }
 
-    for _ in 0..frame.header().question_count() {
-      Question::read(frame.buf, &mut i)?;
+  /// Parse a message prefixed with its 16-bit length, as sent over TCP.
+  ///
+  /// Returns the message together with the number of bytes consumed from `buffer`.
+  pub fn parse_tcp(buffer: &'a mut [u8]) -> Result<(Message<'a>, usize), Error> {
+    if buffer.len() < size_of::<u16>() {
+      return Err(Error::MessageTooShort { offset: 0 })
     }
 
-    for _ in 0..frame.header().answer_count() {
-      Answer::read(frame.buf, &mut i)?;
+    let (prefix, rest) = buffer.split_at_mut(size_of::<u16>());
+    let len = u16::from_be_bytes([prefix[0], prefix[1]]) as usize;
+
+    if rest.len() < len {
+      return Err(Error::MessageTooShort { offset: size_of::<u16>() })
     }
 
-    frame.len = i;
+    let message = Self::parse_with_limit(&mut rest[..len], MAX_TCP_MESSAGE_SIZE)?;
+    Ok((message, size_of::<u16>() + len))
+  }
+
+  /// Parse consecutive messages prefixed with their 16-bit length, as sent over TCP.
+  pub fn parse_stream(buffer: &'a mut [u8]) -> MessageStream<'a> {
+    MessageStream { buf: buffer }
+  }
+
+  /// Parse a message from a read-only buffer.
+  pub fn parse_ref(buffer: &'a [u8]) -> Result<&'a MessageRef, Error> {
+    let len = MessageRef::parse_len(buffer, HEADER_SIZE + MAX_MESSAGE_SIZE)?;
+    Ok(MessageRef::from_bytes(&buffer[..len]))
+  }
+}
+
+/// Iterator over length-prefixed [`Message`](struct.Message.html)s contained in a TCP stream buffer.
+///
+/// Iteration stops once the buffer does not contain another complete frame.
+#[derive(Debug)]
+pub struct MessageStream<'a> {
+  buf: &'a mut [u8],
+}
 
-    Ok(frame)
+impl MessageStream<'_> {
+  /// Returns the number of bytes which have not been consumed yet.
+  #[inline]
+  pub fn remaining(&self) -> usize {
+    self.buf.len()
+  }
+}
+
+impl<'a> Iterator for MessageStream<'a> {
+  type Item = Result<Message<'a>, Error>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.buf.len() < size_of::<u16>() {
+      return None
+    }
+
+    let len = u16::from_be_bytes([self.buf[0], self.buf[1]]) as usize;
+    if self.buf.len() < size_of::<u16>() + len {
+      return None
+    }
+
+    let buf = core::mem::take(&mut self.buf);
+    let (frame, rest) = buf.split_at_mut(size_of::<u16>() + len);
+    self.buf = rest;
+
+    Some(Message::parse_with_limit(&mut frame[size_of::<u16>()..], MAX_TCP_MESSAGE_SIZE))
   }
 }
 
@@ -90,59 +272,448 @@ impl fmt::Debug for Message<'_> {
   }
 }
 
+impl fmt::Display for Message<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    (**self).fmt(f)
+  }
+}
+
+impl fmt::Display for MessageRef {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let header = self.header();
+
+    writeln!(f, ";; ->>HEADER<<- opcode: {}, status: {}, id: {}", header.opcode(), header.response_code(), header.id())?;
+
+    write!(f, ";; flags:")?;
+    for (flag, set) in [
+      ("qr", header.kind() == HeaderKind::Response),
+      ("aa", header.authoritative_answer()),
+      ("tc", header.truncated()),
+      ("rd", header.recursion_desired()),
+      ("ra", header.recursion_available()),
+    ].iter() {
+      if *set {
+        write!(f, " {}", flag)?;
+      }
+    }
+    writeln!(
+      f, "; QUERY: {}, ANSWER: {}, AUTHORITY: {}, ADDITIONAL: {}",
+      header.question_count(), header.answer_count(), header.name_server_count(), header.additional_records_count(),
+    )?;
+
+    if header.question_count() > 0 {
+      writeln!(f)?;
+      writeln!(f, ";; QUESTION SECTION:")?;
+      for question in self.questions() {
+        writeln!(f, ";{:#}\t{}\t{}", question.name(), question.class(), question.kind())?;
+      }
+    }
+
+    let mut current_section = None;
+    for (section, record) in self.records() {
+      if current_section != Some(section) {
+        writeln!(f)?;
+        writeln!(f, ";; {} SECTION:", match section {
+          Section::Answer => "ANSWER",
+          Section::Authority => "AUTHORITY",
+          Section::Additional => "ADDITIONAL",
+        })?;
+
+        current_section = Some(section);
+      }
+
+      write!(f, "{:#}\t{}\t{}\t{}\t", record.name(), record.ttl(), record.class(), record.kind())?;
+      fmt_rdata(&record, f)?;
+      writeln!(f)?;
+    }
+
+    Ok(())
+  }
+}
+
+fn fmt_rdata(answer: &Answer<'_>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+  match answer.parse_rdata() {
+    Rdata::A(addr) => return write!(f, "{}", addr),
+    Rdata::Aaaa(addr) => return write!(f, "{}", addr),
+    Rdata::Cname(name) | Rdata::Ns(name) | Rdata::Ptr(name) | Rdata::Dname(name) => return write!(f, "{:#}", name),
+    Rdata::Mx { preference, exchange } => return write!(f, "{} {:#}", preference, exchange),
+    _ => {},
+  }
+
+  // Unknown RDATA format according to RFC 3597.
+  write!(f, "\\# {}", answer.rdata().len())?;
+  if !answer.rdata().is_empty() {
+    write!(f, " ")?;
+    for b in answer.rdata() {
+      write!(f, "{:02x}", b)?;
+    }
+  }
+
+  Ok(())
+}
+
 impl Deref for Message<'_> {
-  type Target = [u8];
+  type Target = MessageRef;
 
   fn deref(&self) -> &Self::Target {
-    self.as_bytes()
+    MessageRef::from_bytes(self.as_bytes())
   }
 }
 
 impl Message<'_> {
-  pub fn header(&self) -> &Header {
-    unsafe { &*(self.buf[..HEADER_SIZE].as_ptr() as *const _ as *const Header) }
-  }
-
   pub fn header_mut(&mut self) -> &mut Header {
     unsafe { &mut *(self.buf[..HEADER_SIZE].as_mut_ptr() as *mut _ as *mut Header) }
   }
 
-  pub fn add_question(&mut self, question: &Question<'_>) {
-    let mut i = self.questions_end();
-    self.add_name(&mut i, &question.name);
-    self.add_kind(&mut i, &question.kind);
-    self.add_class(&mut i, &question.class);
+  pub fn add_question(&mut self, question: &Question<'_>) -> Result<(), Error> {
+    let i = self.questions_end();
+    self.insert_record(i, |message, i| {
+      message.add_name(i, &question.name)?;
+      message.add_kind(i, &question.kind)?;
+      message.add_class(i, &question.class, question.unicast_response)
+    })?;
 
     let header = self.header_mut();
     unsafe { header.set_question_count(header.question_count() + 1) };
+
+    Ok(())
   }
 
-  pub fn add_answer(&mut self, answer: &Answer<'_>) {
-    let mut i = self.answers_end();
-    self.add_name(&mut i, &answer.name);
-    self.add_kind(&mut i, &answer.kind);
-    self.add_class(&mut i, &answer.class);
-    self.add_ttl(&mut i, answer.ttl);
-    self.add_rdata(&mut i, answer.rdata);
+  pub fn add_answer(&mut self, answer: &Answer<'_>) -> Result<(), Error> {
+    let i = self.answers_end();
+    self.add_record(i, answer)?;
 
     let header = self.header_mut();
     unsafe { header.set_answer_count(header.answer_count() + 1) };
+
+    Ok(())
+  }
+
+  pub fn add_name_server(&mut self, name_server: &Answer<'_>) -> Result<(), Error> {
+    let i = self.name_servers_end();
+    self.add_record(i, name_server)?;
+
+    let header = self.header_mut();
+    unsafe { header.set_name_server_count(header.name_server_count() + 1) };
+
+    Ok(())
+  }
+
+  pub fn add_additional_record(&mut self, additional_record: &Answer<'_>) -> Result<(), Error> {
+    let i = self.additional_records_end();
+    self.add_record(i, additional_record)?;
+
+    let header = self.header_mut();
+    unsafe { header.set_additional_records_count(header.additional_records_count() + 1) };
+
+    Ok(())
+  }
+
+  /// Copy all answers from `other` into this message.
+  ///
+  /// Names are encoded again using the compression of this message, names contained in
+  /// RDATA are written uncompressed. Returns an error if such a name cannot be read.
+  ///
+  /// The message may not grow beyond 512 bytes, or the UDP payload size of an `OPT` record
+  /// in this message if it is larger. If an answer does not fit, the answers copied so far
+  /// are kept and `MessageTooLong` is returned.
+  pub fn append_answers_from(&mut self, other: &MessageRef) -> Result<(), Error> {
+    let opt = self.additional_records().find_map(|record| record.as_opt());
+    let max_len = opt.map_or(0, |opt| usize::from(opt.udp_payload_size())).max(HEADER_SIZE + MAX_MESSAGE_SIZE);
+
+    for answer in other.answers() {
+      self.add_answer(&answer)?;
+
+      if self.len > max_len {
+        self.remove_last_answer();
+        return Err(Error::MessageTooLong)
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Add `record` at `i`, writing all names contained in its RDATA uncompressed, since
+  /// pointers in a record read from another message are only valid in that message.
+  fn add_record(&mut self, i: usize, record: &Answer<'_>) -> Result<(), Error> {
+    self.insert_record(i, |message, i| {
+      message.add_name(i, &record.name)?;
+      message.add_kind(i, &record.kind)?;
+      message.add_class(i, &record.class, record.cache_flush)?;
+      message.add_ttl(i, record.ttl)?;
+      message.add_rdata(i, record)
+    })?;
+
+    Ok(())
   }
 
-  fn add_pointer(&mut self, i: &mut usize, name: &Name<'_>) -> bool {
+  /// Returns the number of unused bytes left in the underlying buffer.
+  #[inline]
+  pub fn remaining(&self) -> usize {
+    self.buf.len() - self.len
+  }
+
+  /// Remove all records, keeping the ID and flags of the header.
+  pub fn clear(&mut self) {
+    let header = self.header_mut();
+    unsafe {
+      header.set_question_count(0);
+      header.set_answer_count(0);
+      header.set_name_server_count(0);
+      header.set_additional_records_count(0);
+    }
+
+    self.len = HEADER_SIZE;
+  }
+
+  /// Set the record counts in the header to the number of records actually contained in the message.
+  ///
+  /// Counts are reduced to the number of records which can be read. Records left over after
+  /// the additional section are counted as additional records and any remaining bytes are removed.
+  pub fn sync_counts(&mut self) {
+    let buf = self.as_bytes();
+    let header = self.header();
+
+    let mut i = HEADER_SIZE;
+
+    let mut question_count = 0;
+    while question_count < header.question_count() && Question::read(buf, &mut i).is_ok() {
+      question_count += 1;
+    }
+
+    let mut counts = [header.answer_count(), header.name_server_count(), header.additional_records_count()];
+    let last = counts.len() - 1;
+    for (n, count) in counts.iter_mut().enumerate() {
+      let max_count = if n == last { u16::MAX } else { *count };
+
+      let mut actual_count = 0;
+      while actual_count < max_count && Answer::read(buf, &mut i).is_ok() {
+        actual_count += 1;
+      }
+
+      *count = actual_count;
+    }
+
+    let header = self.header_mut();
+    unsafe {
+      header.set_question_count(question_count);
+      header.set_answer_count(counts[0]);
+      header.set_name_server_count(counts[1]);
+      header.set_additional_records_count(counts[2]);
+    }
+
+    self.len = i;
+  }
+
+  /// Remove the last question.
+  ///
+  /// Returns `false` if there are no questions or if a record refers to the
+  /// name of the last question using compression.
+  pub fn remove_last_question(&mut self) -> bool {
+    let question_count = self.header().question_count();
+    if question_count == 0 {
+      return false
+    }
+
+    let mut questions = self.questions();
+    for _ in 1..question_count {
+      questions.next();
+    }
+    let start = questions.buf_i;
+    let end = self.questions_end();
+
+    if !self.remove_record(start, end) {
+      return false
+    }
+
+    let header = self.header_mut();
+    unsafe { header.set_question_count(header.question_count() - 1) };
+
+    true
+  }
+
+  /// Remove the last answer.
+  ///
+  /// Returns `false` if there are no answers or if a later record refers to the
+  /// name of the last answer using compression.
+  pub fn remove_last_answer(&mut self) -> bool {
+    let answer_count = self.header().answer_count();
+    if answer_count == 0 {
+      return false
+    }
+
+    let mut answers = self.answers();
+    for _ in 1..answer_count {
+      answers.next();
+    }
+    let start = answers.buf_i;
+    let end = self.answers_end();
+
+    if !self.remove_record(start, end) {
+      return false
+    }
+
+    let header = self.header_mut();
+    unsafe { header.set_answer_count(header.answer_count() - 1) };
+
+    true
+  }
+
+  /// Remove answers with the same name, kind, class and data as a previous answer,
+  /// keeping the smallest TTL.
+  ///
+  /// Duplicates which are referred to by a later record using compression are kept.
+  pub fn dedup_answers(&mut self) {
+    let mut n = 0;
+
+    while n < self.header().answer_count() as usize {
+      let mut m = n + 1;
+
+      while m < self.header().answer_count() as usize {
+        let (ttl_i, ttl, start, end) = {
+          let mut answers = self.answers();
+          let first = answers.nth(n).unwrap();
+          for _ in (n + 1)..m {
+            answers.next();
+          }
+          let start = answers.buf_i;
+          let other = answers.next().unwrap();
+
+          if !first.eq_ignoring_ttl(&other) {
+            m += 1;
+            continue
+          }
+
+          let ttl_i = first.rdata_i - size_of::<u16>() - size_of::<u32>();
+          (ttl_i, first.ttl().min(other.ttl()), start, answers.buf_i)
+        };
+
+        if self.remove_record(start, end) {
+          self.buf[ttl_i..(ttl_i + size_of::<u32>())].copy_from_slice(&ttl.to_be_bytes());
+
+          let header = self.header_mut();
+          unsafe { header.set_answer_count(header.answer_count() - 1) };
+        } else {
+          m += 1;
+        }
+      }
+
+      n += 1;
+    }
+  }
+
+  /// Remove the record at `start..end`, unless a name in a following record points into it.
+  fn remove_record(&mut self, start: usize, end: usize) -> bool {
+    let points_into = for_each_pointer(&mut self.buf[..self.len], end, |_, offset, ptr| {
+      if (start..end).contains(&ptr) {
+        return Err(Error::Pointer { offset })
+      }
+
+      Ok(())
+    });
+
+    if points_into.is_err() {
+      return false
+    }
+
+    self.remove(start, end);
+
+    // Pointers only move towards the start, so they all stay reachable.
+    let _ = self.shift_pointers(start, end, -((end - start) as isize));
+
+    true
+  }
+
+  /// Drop answers from the end until the message fits into a UDP packet.
+  ///
+  /// If any answers are dropped, the truncated flag is set.
+  pub fn truncate_for_udp(&mut self) {
+    let mut truncated = false;
+
+    while self.len > HEADER_SIZE + MAX_MESSAGE_SIZE && self.remove_last_answer() {
+      truncated = true;
+    }
+
+    if truncated {
+      self.header_mut().set_truncated(true);
+    }
+  }
+
+  /// Insert a record at `i` using `f`, removing any partially inserted bytes on error.
+  fn insert_record<F>(&mut self, i: usize, f: F) -> Result<usize, Error>
+  where
+    F: FnOnce(&mut Self, &mut usize) -> Result<(), Error>,
+  {
+    let mut end = i;
+
+    if let Err(err) = f(self, &mut end).and_then(|()| self.shift_pointers(end, i, (end - i) as isize)) {
+      self.remove(i, end);
+      return Err(err)
+    }
+
+    Ok(end)
+  }
+
+  /// Add `delta` to all pointers in resource records starting at `i` which point at or after `target`.
+  ///
+  /// All pointers are checked first, so nothing is changed if any of them would become unreachable.
+  fn shift_pointers(&mut self, i: usize, target: usize, delta: isize) -> Result<(), Error> {
+    let shift = |offset: usize, ptr: usize| {
+      let ptr = if ptr >= target { (ptr as isize + delta) as usize } else { ptr };
+      pointer(ptr).ok_or(Error::Pointer { offset })
+    };
+
+    let buf = &mut self.buf[..self.len];
+    for_each_pointer(buf, i, |_, offset, ptr| shift(offset, ptr).map(|_| ()))?;
+    for_each_pointer(buf, i, |buf, offset, ptr| {
+      buf[offset..(offset + size_of::<u16>())].copy_from_slice(&shift(offset, ptr)?);
+      Ok(())
+    })
+  }
+
+  /// Find a pointer to `name` in the names of all questions and records before `end`.
+  fn find_pointer(&self, end: usize, name: &Name<'_>) -> Option<[u8; 2]> {
     for question in self.questions() {
       if let Some(pointer) = question.name().create_pointer(name) {
-        self.insert(i, &pointer);
-        return true
+        return Some(pointer)
       }
     }
 
-    false
+    let mut records = self.records();
+    while records.buf_i < end {
+      let (_, record) = records.next()?;
+
+      if let Some(pointer) = record.name().create_pointer(name) {
+        return Some(pointer)
+      }
+    }
+
+    None
+  }
+
+  fn add_pointer(&mut self, i: &mut usize, end: usize, name: &Name<'_>) -> Result<bool, Error> {
+    if let Some(pointer) = self.find_pointer(end, name) {
+      self.insert(i, &pointer)?;
+      return Ok(true)
+    }
+
+    Ok(false)
   }
 
-  fn add_name(&mut self, i: &mut usize, name: &Name<'_>) {
-    if self.add_pointer(i, name) {
-      return;
+  fn add_name(&mut self, i: &mut usize, name: &Name<'_>) -> Result<(), Error> {
+    if name.byte_len() > MAX_NAME_LEN {
+      return Err(Error::NameTooLong)
+    }
+
+    // Only records before this name are complete and can be pointed to.
+    let end = *i;
+
+    if self.add_pointer(i, end, name)? {
+      return Ok(());
+    }
+
+    if name.labels().next().is_none() {
+      return self.insert(i, &[0]);
     }
 
     let mut name_rest: Option<Name<'_>> = None;
@@ -153,13 +724,13 @@ impl Message<'_> {
         name.split()
       };
 
-      self.insert(i, &[label.len() as u8]);
-      self.insert(i, label.as_bytes());
+      self.insert(i, &[label.len() as u8])?;
+      self.insert(i, label.as_bytes())?;
 
       match name {
         Some(rest) => {
-          if self.add_pointer(i, &rest) {
-            return;
+          if self.add_pointer(i, end, &rest)? {
+            return Ok(());
           }
 
           name_rest = Some(rest);
@@ -168,55 +739,266 @@ impl Message<'_> {
       }
     }
 
-    self.insert(i, &[0]);
+    self.insert(i, &[0])
   }
 
-  fn add_kind(&mut self, i: &mut usize, kind: &QueryKind) {
-    self.insert(i, &kind.to_be_bytes());
+  fn add_kind(&mut self, i: &mut usize, kind: &QueryKind) -> Result<(), Error> {
+    self.insert(i, &kind.to_be_bytes())
   }
 
-  fn add_class(&mut self, i: &mut usize, class: &QueryClass) {
-    self.insert(i, &class.to_be_bytes());
+  fn add_class(&mut self, i: &mut usize, class: &QueryClass, mdns_flag: bool) -> Result<(), Error> {
+    self.insert(i, &class.to_be_bytes_with_mdns_flag(mdns_flag))
   }
 
-  fn add_ttl(&mut self, i: &mut usize, ttl: u32) {
-    self.insert(i, &ttl.to_be_bytes());
+  fn add_ttl(&mut self, i: &mut usize, ttl: u32) -> Result<(), Error> {
+    self.insert(i, &ttl.to_be_bytes())
   }
 
-  fn add_rdata(&mut self, i: &mut usize, data: &[u8]) {
-    self.insert(i, &(data.len() as u16).to_be_bytes());
-    self.insert(i, data);
+  /// Add the RDATA of `record`, writing all names it contains uncompressed.
+  fn add_rdata(&mut self, i: &mut usize, record: &Answer<'_>) -> Result<(), Error> {
+    let rdata_len_i = *i;
+    self.insert(i, &[0, 0])?;
+    record.write_rdata(|bytes| self.insert(i, bytes))?;
+
+    let rdata_len = u16::try_from(*i - rdata_len_i - size_of::<u16>()).map_err(|_| Error::MessageTooLong)?;
+    self.buf[rdata_len_i..(rdata_len_i + size_of::<u16>())].copy_from_slice(&rdata_len.to_be_bytes());
+
+    Ok(())
   }
 
-  fn insert(&mut self, i: &mut usize, bytes: &[u8]) {
+  fn insert(&mut self, i: &mut usize, bytes: &[u8]) -> Result<(), Error> {
     let len = bytes.len();
 
-    for j in (*i..self.len).rev() {
-      self.buf[j + len] = self.buf[j];
+    if self.len + len > self.buf.len() {
+      return Err(Error::MessageTooLong)
     }
 
+    self.buf.copy_within(*i..self.len, *i + len);
     self.buf[*i..(*i + len)].copy_from_slice(bytes);
 
     self.len += len;
 
     *i += len;
+
+    Ok(())
+  }
+
+  fn remove(&mut self, start: usize, end: usize) {
+    self.buf.copy_within(end..self.len, start);
+    self.len -= end - start;
   }
 
   pub fn as_bytes(&self) -> &[u8] {
     &self.buf[..self.len]
   }
+}
+
+/// Call `f` with the offset and target of the pointer in every compressed name of the resource
+/// records starting at `i`, including names contained in RDATA.
+///
+/// Pointers are not followed, so this works while they are being shifted.
+fn for_each_pointer<F>(buf: &mut [u8], mut i: usize, mut f: F) -> Result<(), Error>
+where
+  F: FnMut(&mut [u8], usize, usize) -> Result<(), Error>,
+{
+  while i < buf.len() {
+    if let Some((offset, ptr)) = skip_name(buf, &mut i)? {
+      f(buf, offset, ptr)?;
+    }
+
+    let kind = QueryKind::read(buf, &mut i)?;
+
+    // Skip class and TTL.
+    i += size_of::<u16>() + size_of::<u32>();
+
+    let rdata_len = match buf.get(i..(i + size_of::<u16>())) {
+      Some(bytes) => u16::from_be_bytes([bytes[0], bytes[1]]) as usize,
+      None => return Err(Error::MessageTooShort { offset: i }),
+    };
+    let rdata_i = i + size_of::<u16>();
+    let rdata_end = rdata_i + rdata_len;
+    let rdata = buf.get(rdata_i..rdata_end).ok_or(Error::MessageTooShort { offset: i })?;
+
+    if let Some((offset, count)) = rdata_names(kind, rdata) {
+      let mut j = rdata_i + offset;
+
+      for _ in 0..count {
+        // Names in malformed RDATA cannot be read and are left alone.
+        match skip_name(&buf[..rdata_end], &mut j) {
+          Ok(Some((offset, ptr))) => f(buf, offset, ptr)?,
+          Ok(None) => continue,
+          Err(_) => break,
+        }
+      }
+    }
+
+    i = rdata_end;
+  }
+
+  Ok(())
+}
+
+/// A read-only DNS message.
+///
+/// Returned by [`Message::parse_ref`](struct.Message.html#method.parse_ref). A
+/// [`Message`](struct.Message.html) dereferences to this type, so all read-only
+/// methods are available on both.
+#[repr(transparent)]
+pub struct MessageRef([u8]);
+
+impl MessageRef {
+  /// Validate the message contained in `buffer` of at most `max_len` bytes and return its length.
+  fn parse_len(buffer: &[u8], max_len: usize) -> Result<usize, Error> {
+    if buffer.len() < HEADER_SIZE {
+      return Err(Error::MessageTooShort { offset: 0 })
+    } else if buffer.len() > max_len {
+      return Err(Error::MessageTooLong)
+    }
+
+    let message = Self::from_bytes(buffer);
+
+    let mut i = HEADER_SIZE;
+
+    for _ in 0..message.header().question_count() {
+      Question::read(buffer, &mut i)?;
+    }
+
+    let header = message.header();
+    let record_count = header.answer_count() as usize + header.name_server_count() as usize + header.additional_records_count() as usize;
+    for _ in 0..record_count {
+      Answer::read(buffer, &mut i)?;
+    }
+
+    Ok(i)
+  }
+
+  /// Check the strict requirements of [`Message::parse_strict`](struct.Message.html#method.parse_strict).
+  fn validate(&self) -> Result<(), Error> {
+    for question in self.questions() {
+      question.name().validate()?;
+    }
+
+    for (_, record) in self.records() {
+      record.name().validate()?;
+      record.validate()?;
+    }
+
+    Ok(())
+  }
+
+  /// `bytes` must contain at least a complete header.
+  fn from_bytes(bytes: &[u8]) -> &Self {
+    debug_assert!(bytes.len() >= HEADER_SIZE);
+    unsafe { &*(bytes as *const [u8] as *const Self) }
+  }
+
+  pub fn header(&self) -> &Header {
+    unsafe { &*(self.0[..HEADER_SIZE].as_ptr() as *const _ as *const Header) }
+  }
+
+  #[inline]
+  pub fn is_query(&self) -> bool {
+    self.header().kind() == HeaderKind::Query
+  }
+
+  #[inline]
+  pub fn is_response(&self) -> bool {
+    self.header().kind() == HeaderKind::Response
+  }
+
+  pub fn as_bytes(&self) -> &[u8] {
+    &self.0
+  }
+
+  /// Returns an owned copy of the bytes of this message.
+  #[cfg(feature = "alloc")]
+  pub fn to_vec(&self) -> Vec<u8> {
+    self.0.to_vec()
+  }
+
+  /// Returns the full 12-bit response code.
+  ///
+  /// If the additional section contains an `OPT` record, the upper 8 bits are taken
+  /// from its TTL field according to [RFC 6891](https://tools.ietf.org/rfc/rfc6891#section-6.1.3),
+  /// otherwise this is the same as the response code in the header.
+  pub fn extended_response_code(&self) -> ResponseCode {
+    let response_code = u16::from(self.header().response_code());
+
+    let opt = self.additional_records().find_map(|record| record.as_opt());
+    let extended_response_code = opt.map(|opt| opt.extended_response_code() as u16).unwrap_or(0);
+
+    ResponseCode::from(extended_response_code << 4 | response_code)
+  }
+
+  /// Write the bytes of this message to `w`.
+  #[cfg(feature = "std")]
+  pub fn write_to<W: Write + ?Sized>(&self, w: &mut W) -> std::io::Result<()> {
+    w.write_all(&self.0)
+  }
+
+  /// Write the bytes of this message to `w`.
+  #[cfg(not(feature = "std"))]
+  pub fn write_to<W: Write + ?Sized>(&self, w: &mut W) -> Result<(), Error> {
+    w.write_all(&self.0)
+  }
+
+  /// Write this message prefixed with its 16-bit length, as sent over TCP.
+  ///
+  /// Returns the number of bytes written to `out`.
+  pub fn write_tcp(&self, out: &mut [u8]) -> Result<usize, Error> {
+    let len = self.0.len();
+
+    if len > u16::MAX as usize {
+      return Err(Error::MessageTooLong)
+    }
+
+    let total_len = size_of::<u16>() + len;
+    let out = out.get_mut(..total_len).ok_or(Error::BufferTooSmall)?;
+
+    out[..size_of::<u16>()].copy_from_slice(&(len as u16).to_be_bytes());
+    out[size_of::<u16>()..].copy_from_slice(&self.0);
+
+    Ok(total_len)
+  }
+
+  /// Compare two messages semantically, ignoring the transaction ID.
+  ///
+  /// Names are compared by their labels, so messages using different
+  /// name compression are still considered equal.
+  pub fn eq_ignoring_id(&self, other: &MessageRef) -> bool {
+    if !self.header().eq_ignoring_id(other.header()) {
+      return false
+    }
+
+    let questions_eq = self.questions().zip(other.questions()).all(|(q1, q2)| {
+      q1.name() == q2.name() && q1.kind() == q2.kind() && q1.class() == q2.class() &&
+        q1.unicast_response() == q2.unicast_response()
+    });
+
+    let records_eq = self.records().zip(other.records()).all(|((s1, r1), (s2, r2))| {
+      s1 == s2 && r1.ttl() == r2.ttl() && r1.eq_ignoring_ttl(&r2)
+    });
+
+    questions_eq && records_eq
+  }
 
   pub fn questions(&self) -> Questions<'_> {
     Questions {
       question_count: self.header().question_count() as usize,
       current_question: 0,
-      buf: self.as_bytes(),
+      buf: &self.0,
       buf_i: HEADER_SIZE,
     }
   }
 
+  /// Returns the question at `index`, reading all preceding questions.
+  #[inline]
+  pub fn question(&self, index: usize) -> Option<Question<'_>> {
+    self.questions().nth(index)
+  }
+
   fn questions_end(&self) -> usize {
-    let buf = &self.as_bytes();
+    let buf = &self.0;
     let mut i = HEADER_SIZE;
 
     for _ in 0..self.header().question_count() {
@@ -230,16 +1012,102 @@ impl Message<'_> {
     Answers {
       answer_count: self.header().answer_count() as usize,
       current_answer: 0,
-      buf: self.as_bytes(),
+      buf: &self.0,
       buf_i: self.questions_end(),
     }
   }
 
+  /// Returns the answer at `index`, reading all preceding records.
+  #[inline]
+  pub fn answer(&self, index: usize) -> Option<Answer<'_>> {
+    self.answers().nth(index)
+  }
+
+  /// Returns an iterator over the answers with the given kind.
+  pub fn answers_of_kind(&self, kind: QueryKind) -> impl Iterator<Item = Answer<'_>> {
+    self.answers().filter(move |answer| *answer.kind() == kind)
+  }
+
+  /// Returns the first answer whose owner name is equal to `name`, ignoring ASCII case.
+  pub fn find_answer(&self, name: &Name<'_>) -> Option<Answer<'_>> {
+    self.answers().find(|answer| answer.name() == name)
+  }
+
+  /// Follow `CNAME` answers starting at `start` and return the name at the end of the chain.
+  ///
+  /// Returns `None` if there is no `CNAME` answer for `start` or if the chain contains a loop.
+  pub fn resolve_cname(&self, start: &Name<'_>) -> Option<Name<'_>> {
+    let find_target = |name: &Name<'_>| {
+      self.answers_of_kind(QueryKind::CNAME).find(|answer| answer.name() == name)?.rdata_name()
+    };
+
+    let mut name = find_target(start)?;
+
+    // Every hop uses a different answer, so a longer chain must contain a loop.
+    for _ in 1..self.header().answer_count() {
+      match find_target(&name) {
+        Some(target) => name = target,
+        None => return Some(name),
+      }
+    }
+
+    if find_target(&name).is_some() {
+      return None
+    }
+
+    Some(name)
+  }
+
   fn answers_end(&self) -> usize {
-    let buf = &self.as_bytes();
-    let mut i = self.questions_end();
+    self.records_end(self.questions_end(), self.header().answer_count())
+  }
+
+  pub fn name_servers(&self) -> Answers<'_> {
+    Answers {
+      answer_count: self.header().name_server_count() as usize,
+      current_answer: 0,
+      buf: &self.0,
+      buf_i: self.answers_end(),
+    }
+  }
+
+  fn name_servers_end(&self) -> usize {
+    self.records_end(self.answers_end(), self.header().name_server_count())
+  }
+
+  pub fn additional_records(&self) -> Answers<'_> {
+    Answers {
+      answer_count: self.header().additional_records_count() as usize,
+      current_answer: 0,
+      buf: &self.0,
+      buf_i: self.name_servers_end(),
+    }
+  }
+
+  fn additional_records_end(&self) -> usize {
+    self.records_end(self.name_servers_end(), self.header().additional_records_count())
+  }
+
+  /// Returns an iterator over the records of the answer, authority and additional
+  /// sections together with the section they are contained in.
+  pub fn records(&self) -> Records<'_> {
+    let header = self.header();
+
+    Records {
+      answer_count: header.answer_count() as usize,
+      name_server_count: header.name_server_count() as usize,
+      additional_records_count: header.additional_records_count() as usize,
+      current_record: 0,
+      buf: &self.0,
+      buf_i: self.questions_end(),
+    }
+  }
 
-    for _ in 0..self.header().answer_count() {
+  fn records_end(&self, start: usize, count: u16) -> usize {
+    let buf = &self.0;
+    let mut i = start;
+
+    for _ in 0..count {
       assert!(Answer::read(buf, &mut i).is_ok());
     }
 
@@ -247,6 +1115,23 @@ impl Message<'_> {
   }
 }
 
+impl fmt::Debug for MessageRef {
+  fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+    fmt.debug_struct("MessageRef")
+      .field("header", &self.header())
+      .field("body", &format_args!("{:?}", &self.0[HEADER_SIZE..]))
+      .finish()
+  }
+}
+
+impl Deref for MessageRef {
+  type Target = [u8];
+
+  fn deref(&self) -> &Self::Target {
+    self.as_bytes()
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -271,4 +1156,26 @@ mod tests {
 
     assert!(name.is_none());
   }
+
+  #[test]
+  fn test_add_name_too_long() {
+    let mut bytes = [0; 257];
+    for i in 0..128 {
+      bytes[i * 2] = 1;
+      bytes[i * 2 + 1] = b'a';
+    }
+
+    let mut buf = Message::BUFFER;
+    let mut message = Message::builder(&mut buf).build();
+
+    let name = Name::from_bytes(&bytes);
+    let question = Question::new(name, QueryKind::A, QueryClass::IN);
+    assert_eq!(message.add_question(&question), Err(Error::NameTooLong));
+    assert_eq!(message.as_bytes().len(), HEADER_SIZE);
+    assert_eq!(message.header().question_count(), 0);
+
+    let name = Name::from_bytes(&bytes[2..]);
+    let question = Question::new(name, QueryKind::A, QueryClass::IN);
+    assert!(message.add_question(&question).is_ok());
+  }
 }
@@ -1,7 +1,19 @@
+use core::cmp::Ordering;
 use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::mem::size_of;
+use core::net::IpAddr;
 use core::str;
 
 use crate::Error;
+use crate::message::HEADER_SIZE;
+#[cfg(feature = "heapless")]
+use crate::NameBuf;
+
+pub(crate) const MAX_NAME_LEN: usize = 255;
+const MAX_LABEL_LEN: usize = 63;
+/// Every label takes up at least two bytes, excluding the terminating zero.
+const MAX_LABEL_COUNT: usize = (MAX_NAME_LEN - 1) / 2;
 
 /// A DNS name.
 #[derive(Debug, Clone)]
@@ -16,6 +28,95 @@ impl<'a> Name<'a> {
     Self { buf: bytes, start: 0 }
   }
 
+  /// Returns the root name.
+  #[inline]
+  pub fn root() -> Name<'static> {
+    Name { buf: &[0], start: 0 }
+  }
+
+  /// Returns whether this is the root name.
+  #[inline]
+  pub fn is_root(&self) -> bool {
+    self.labels().next().is_none()
+  }
+
+  /// Parse a dotted name like `captive.apple.com` into `buf`.
+  ///
+  /// A single trailing dot is accepted. An empty string or `.` denotes the root name.
+  /// Labels may contain `\.`, `\\` and `\DDD` escapes according to [RFC 4343](https://tools.ietf.org/rfc/rfc4343#section-2.1).
+  pub fn parse_str(s: &str, buf: &'a mut [u8]) -> Result<Self, Error> {
+    let s = s.as_bytes();
+    let mut i = 0;
+
+    let mut len = 0;
+    let mut label = [0; MAX_LABEL_LEN];
+
+    if s != b"." {
+      while i < s.len() {
+        let label_len = parse_label(s, &mut i, &mut label)?;
+
+        if label_len == 0 {
+          return Err(Error::EmptyLabel)
+        }
+
+        // Leave room for the terminating zero.
+        if len + 1 + label_len + 1 > MAX_NAME_LEN {
+          return Err(Error::NameTooLong)
+        }
+
+        push_label(buf, &mut len, &label[..label_len])?;
+      }
+    }
+
+    *buf.get_mut(len).ok_or(Error::BufferTooSmall)? = 0;
+
+    Ok(Self { buf: &buf[..=len], start: 0 })
+  }
+
+  /// Create the reverse lookup name for `addr` in `buf`, i.e. `1.2.0.192.in-addr.arpa`
+  /// for IPv4 addresses and the reversed nibble form under `ip6.arpa` for IPv6 addresses.
+  pub fn from_reverse(addr: IpAddr, buf: &'a mut [u8]) -> Result<Self, Error> {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+
+    let mut len = 0;
+
+    match addr {
+      IpAddr::V4(addr) => {
+        for &octet in addr.octets().iter().rev() {
+          let mut digits = [0; 3];
+          let mut n = octet;
+          let mut i = digits.len();
+          loop {
+            i -= 1;
+            digits[i] = b'0' + n % 10;
+            n /= 10;
+
+            if n == 0 {
+              break
+            }
+          }
+
+          push_label(buf, &mut len, &digits[i..])?;
+        }
+
+        push_label(buf, &mut len, b"in-addr")?;
+      },
+      IpAddr::V6(addr) => {
+        for &octet in addr.octets().iter().rev() {
+          push_label(buf, &mut len, &[HEX[(octet & 0xf) as usize]])?;
+          push_label(buf, &mut len, &[HEX[(octet >> 4) as usize]])?;
+        }
+
+        push_label(buf, &mut len, b"ip6")?;
+      },
+    }
+
+    push_label(buf, &mut len, b"arpa")?;
+    *buf.get_mut(len).ok_or(Error::BufferTooSmall)? = 0;
+
+    Ok(Self { buf: &buf[..=len], start: 0 })
+  }
+
   pub(crate) fn create_pointer(&self, sub_name: &Name<'_>) -> Option<[u8; 2]> {
     let mut labels = self.labels();
 
@@ -24,8 +125,7 @@ impl<'a> Name<'a> {
 
       if labels.next().is_some() {
         if self.equal_from(i, sub_name) {
-          let [ptr_1, ptr_2] = (i as u16).to_be_bytes();
-          return Some([ptr_1 | PTR_MASK, ptr_2]);
+          return pointer(i)
         }
 
         continue;
@@ -38,12 +138,12 @@ impl<'a> Name<'a> {
   }
 
   fn equal_from(&self, i: usize, sub_name: &Name<'_>) -> bool {
-    let mut this = Labels { buf: self.buf, buf_i: i };
+    let mut this = Labels::new(self.buf, i);
     let mut other = sub_name.labels();
 
     loop {
       match (this.next(), other.next()) {
-        (Some(t), Some(o)) => if !t.as_str().eq_ignore_ascii_case(o.as_str()) {
+        (Some(t), Some(o)) => if !t.as_bytes().eq_ignore_ascii_case(o.as_bytes()) {
           break;
         },
         (None, None) => return true,
@@ -59,16 +159,30 @@ impl<'a> Name<'a> {
     let mut maximum = *i;
     let mut ptr = None;
 
-    let mut len: u8 = 0;
+    // Length of the name on the wire, including the terminating zero.
+    let mut len: usize = 1;
 
     loop {
-      match LabelType::read(buf, ptr.as_mut().unwrap_or(&mut j))? {
+      let k = ptr.as_mut().unwrap_or(&mut j);
+      let offset = *k;
+
+      let label_type = LabelType::read(buf, k)?;
+      let end = *k;
+
+      // Labels reached through a pointer must end before the first pointer,
+      // otherwise they could extend past the end of the message.
+      if ptr.is_some() && end > j - size_of::<u16>() {
+        return Err(Error::Pointer { offset })
+      }
+
+      match label_type {
         LabelType::Pointer(p) => {
           let p = p as usize;
 
-          // Pointers can only point to previous occurences.
-          if p >= maximum {
-            return Err(Error::Pointer)
+          // Pointers can only point to previous occurences, which also keeps them
+          // within the buffer, and never into the header.
+          if p >= maximum || p < HEADER_SIZE {
+            return Err(Error::Pointer { offset })
           }
 
           maximum = p;
@@ -86,16 +200,30 @@ impl<'a> Name<'a> {
           }
 
           // Stop if maximum name length of 255 bytes is reached.
-          if let Some(l) = len.checked_add(part_len) {
-            len = l;
-          } else {
+          len += 1 + part_len as usize;
+          if len > MAX_NAME_LEN {
             return Err(Error::NameTooLong)
-          };
+          }
         },
       }
     }
   }
 
+  /// Returns the offset of the first pointer in this name, if it is compressed.
+  pub(crate) fn pointer_offset(&self) -> Option<usize> {
+    let mut i = self.start;
+
+    loop {
+      let offset = i;
+
+      match LabelType::read(self.buf, &mut i) {
+        Ok(LabelType::Pointer(_)) => return Some(offset),
+        Ok(LabelType::Part(0)) | Err(_) => return None,
+        Ok(LabelType::Part(_)) => continue,
+      }
+    }
+  }
+
   pub(crate) fn split(&self) -> (Label<'a>, Option<Name<'a>>) {
     let mut labels = self.labels();
 
@@ -105,14 +233,149 @@ impl<'a> Name<'a> {
     (label, name)
   }
 
-  pub(crate) fn labels(&self) -> Labels<'a> {
-    Labels {
-      buf: self.buf,
-      buf_i: self.start,
+  /// Check that all labels only consist of letters, digits, hyphens and underscores.
+  /// The first label may also be a `*` wildcard, e.g. `*.example.com`.
+  ///
+  /// Labels using the reserved label types `0b01` and `0b10`, see
+  /// [RFC 6891 Section 5](https://tools.ietf.org/rfc/rfc6891#section-5), are rejected as well.
+  pub fn validate(&self) -> Result<(), Error> {
+    for (i, label) in self.labels().enumerate() {
+      if self.buf[label.buf_i] & PTR_MASK != 0 {
+        return Err(Error::InvalidLabel { offset: label.buf_i })
+      }
+
+      if i == 0 && label.as_bytes() == b"*" {
+        continue
+      }
+
+      if !label.as_bytes().iter().all(|&b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_') {
+        return Err(Error::InvalidLabel { offset: label.buf_i })
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Write the uncompressed, lowercase wire form of this name into `out` as
+  /// required for DNSSEC, see [RFC 4034 Section 6.2](https://tools.ietf.org/rfc/rfc4034#section-6.2).
+  ///
+  /// Returns the number of bytes written.
+  pub fn write_canonical(&self, out: &mut [u8]) -> Result<usize, Error> {
+    let len = self.write(out)?;
+    out[..len].make_ascii_lowercase();
+    Ok(len)
+  }
+
+  /// Write the uncompressed wire form of this name into `out`.
+  ///
+  /// Returns the number of bytes written.
+  pub(crate) fn write(&self, out: &mut [u8]) -> Result<usize, Error> {
+    let mut len = 0;
+
+    for label in self.labels() {
+      push_label(out, &mut len, label.as_bytes())?;
+    }
+
+    *out.get_mut(len).ok_or(Error::BufferTooSmall)? = 0;
+
+    Ok(len + 1)
+  }
+
+  /// Returns the number of labels in this name.
+  pub fn label_count(&self) -> usize {
+    self.labels().count()
+  }
+
+  /// Returns the length of this name on the wire without compression,
+  /// including the terminating zero.
+  pub fn byte_len(&self) -> usize {
+    self.labels().map(|label| 1 + label.len()).sum::<usize>() + 1
+  }
+
+  /// Write the dotted form of this name, as formatted by `Display`, into `out`.
+  ///
+  /// Returns the written part of `out` as a string.
+  pub fn write_str<'b>(&self, out: &'b mut [u8]) -> Result<&'b str, Error> {
+    let mut writer = SliceWriter { buf: out, len: 0 };
+    fmt::write(&mut writer, format_args!("{}", self)).map_err(|_| Error::BufferTooSmall)?;
+
+    let SliceWriter { buf, len } = writer;
+
+    // Only complete strings are written, so the output is valid UTF-8.
+    Ok(unsafe { str::from_utf8_unchecked(&buf[..len]) })
+  }
+
+  /// Returns an iterator over the labels of this name, following compression pointers.
+  ///
+  /// Labels which are not valid UTF-8 are returned as `"\u{FFFD}"`.
+  pub fn labels_str(&self) -> impl Iterator<Item = &'a str> {
+    self.labels().map(|label| label.as_str())
+  }
+
+  /// Returns an iterator over the labels of this name from the rightmost to the leftmost one.
+  ///
+  /// Labels which are not valid UTF-8 are returned as `"\u{FFFD}"`.
+  pub fn labels_rev(&self) -> impl Iterator<Item = &'a str> {
+    let mut offsets = [0; MAX_LABEL_COUNT];
+    let count = self.label_offsets(&mut offsets);
+    let buf = self.buf;
+
+    (0..count).rev().map(move |n| Label { buf, buf_i: offsets[n] }.as_str())
+  }
+
+  /// Copy this name into a self-contained, uncompressed buffer.
+  #[cfg(feature = "heapless")]
+  pub fn to_owned(&self) -> NameBuf {
+    NameBuf::from_name(self)
+  }
+
+  /// Returns whether the rightmost labels of this name are equal to the labels of `other`.
+  ///
+  /// Every name ends with itself and with the root name.
+  pub fn ends_with(&self, other: &Name<'_>) -> bool {
+    let mut offsets = [0; MAX_LABEL_COUNT];
+    let count = self.label_offsets(&mut offsets);
+    let mut other_offsets = [0; MAX_LABEL_COUNT];
+    let other_count = other.label_offsets(&mut other_offsets);
+
+    if other_count > count {
+      return false
+    }
+
+    offsets[(count - other_count)..count].iter().zip(other_offsets[..other_count].iter()).all(|(&buf_i, &other_buf_i)| {
+      let label = Label { buf: self.buf, buf_i };
+      let other_label = Label { buf: other.buf, buf_i: other_buf_i };
+      label.as_bytes().eq_ignore_ascii_case(other_label.as_bytes())
+    })
+  }
+
+  /// Returns whether this name is contained in `zone`, excluding `zone` itself.
+  pub fn is_subdomain_of(&self, zone: &Name<'_>) -> bool {
+    self.label_count() > zone.label_count() && self.ends_with(zone)
+  }
+
+  /// Store the offsets of all labels in `offsets` and return the number of labels.
+  fn label_offsets(&self, offsets: &mut [usize; MAX_LABEL_COUNT]) -> usize {
+    let mut count = 0;
+
+    for (offset, label) in offsets.iter_mut().zip(self.labels()) {
+      *offset = label.buf_i;
+      count += 1;
     }
+
+    count
+  }
+
+  pub(crate) fn labels(&self) -> Labels<'a> {
+    Labels::new(self.buf, self.start)
   }
 }
 
+/// Dots and backslashes within labels are escaped as `\.` and `\\`, other
+/// non-printable bytes as `\DDD` according to [RFC 4343](https://tools.ietf.org/rfc/rfc4343#section-2.1).
+///
+/// The alternate format `{:#}` prints the fully qualified name including the
+/// trailing dot, so the root name is printed as `.`.
 impl fmt::Display for Name<'_> {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     let mut print_dot = false;
@@ -122,11 +385,21 @@ impl fmt::Display for Name<'_> {
         '.'.fmt(f)?;
       }
 
-      label.as_str().fmt(f)?;
+      for &b in label.as_bytes() {
+        match b {
+          b'.' | b'\\' => write!(f, "\\{}", b as char)?,
+          0x21..=0x7E => (b as char).fmt(f)?,
+          _ => write!(f, "\\{:03}", b)?,
+        }
+      }
 
       print_dot = true;
     }
 
+    if f.alternate() {
+      '.'.fmt(f)?;
+    }
+
     Ok(())
   }
 }
@@ -161,10 +434,145 @@ impl PartialEq<str> for Name<'_> {
   }
 }
 
+impl<'b> PartialEq<Name<'b>> for Name<'_> {
+  fn eq(&self, other: &Name<'b>) -> bool {
+    self.equal_from(self.start, other)
+  }
+}
+
+impl Eq for Name<'_> {}
+
+impl<'b> PartialOrd<Name<'b>> for Name<'_> {
+  fn partial_cmp(&self, other: &Name<'b>) -> Option<Ordering> {
+    let mut offsets = [0; MAX_LABEL_COUNT];
+    let count = self.label_offsets(&mut offsets);
+    let mut other_offsets = [0; MAX_LABEL_COUNT];
+    let other_count = other.label_offsets(&mut other_offsets);
+
+    // Compare labels starting from the rightmost one, according to
+    // https://tools.ietf.org/rfc/rfc4034#section-6.1.
+    let labels = offsets[..count].iter().rev().map(|&buf_i| Label { buf: self.buf, buf_i });
+    let other_labels = other_offsets[..other_count].iter().rev().map(|&buf_i| Label { buf: other.buf, buf_i });
+
+    for (label, other_label) in labels.zip(other_labels) {
+      let bytes = label.as_bytes().iter().map(u8::to_ascii_lowercase);
+      let other_bytes = other_label.as_bytes().iter().map(u8::to_ascii_lowercase);
+
+      match bytes.cmp(other_bytes) {
+        Ordering::Equal => continue,
+        ordering => return Some(ordering),
+      }
+    }
+
+    Some(count.cmp(&other_count))
+  }
+}
+
+impl Ord for Name<'_> {
+  fn cmp(&self, other: &Self) -> Ordering {
+    self.partial_cmp(other).unwrap()
+  }
+}
+
+impl Hash for Name<'_> {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    for label in self.labels() {
+      state.write_u8(label.len() as u8);
+
+      for b in label.as_bytes() {
+        state.write_u8(b.to_ascii_lowercase());
+      }
+    }
+
+    state.write_u8(0);
+  }
+}
+
+/// A `fmt::Write` implementation writing into a fixed buffer.
+struct SliceWriter<'a> {
+  buf: &'a mut [u8],
+  len: usize,
+}
+
+impl fmt::Write for SliceWriter<'_> {
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    push_bytes(self.buf, &mut self.len, s.as_bytes()).map_err(|_| fmt::Error)
+  }
+}
+
+/// Decode a single, possibly escaped label starting at `i` into `label`, consuming the following dot.
+fn parse_label(s: &[u8], i: &mut usize, label: &mut [u8; MAX_LABEL_LEN]) -> Result<usize, Error> {
+  let mut len = 0;
+
+  while let Some(&c) = s.get(*i) {
+    let b = match c {
+      b'.' => {
+        *i += 1;
+        break
+      },
+      b'\\' => match s.get(*i + 1) {
+        Some(d) if d.is_ascii_digit() => {
+          let n = s.get((*i + 1)..(*i + 4))
+            .filter(|digits| digits.iter().all(u8::is_ascii_digit))
+            .map(|digits| digits.iter().fold(0u16, |n, d| n * 10 + u16::from(d - b'0')))
+            .filter(|&n| n <= u16::from(u8::MAX))
+            .ok_or(Error::InvalidLabel { offset: *i })?;
+          *i += 4;
+          n as u8
+        },
+        Some(&b) => {
+          *i += 2;
+          b
+        },
+        None => return Err(Error::InvalidLabel { offset: *i }),
+      },
+      b => {
+        *i += 1;
+        b
+      },
+    };
+
+    *label.get_mut(len).ok_or(Error::LabelTooLong)? = b;
+    len += 1;
+  }
+
+  Ok(len)
+}
+
+/// Append `bytes` to `buf`, which is filled up to `len`.
+pub(crate) fn push_bytes(buf: &mut [u8], len: &mut usize, bytes: &[u8]) -> Result<(), Error> {
+  let end = *len + bytes.len();
+
+  buf.get_mut(*len..end).ok_or(Error::BufferTooSmall)?.copy_from_slice(bytes);
+
+  *len = end;
+
+  Ok(())
+}
+
+fn push_label(buf: &mut [u8], len: &mut usize, label: &[u8]) -> Result<(), Error> {
+  let end = *len + 1 + label.len();
+
+  let part = buf.get_mut(*len..end).ok_or(Error::BufferTooSmall)?;
+  part[0] = label.len() as u8;
+  part[1..].copy_from_slice(label);
+
+  *len = end;
+
+  Ok(())
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct Labels<'a> {
   buf: &'a [u8],
   buf_i: usize,
+  maximum: usize,
+}
+
+impl<'a> Labels<'a> {
+  pub(crate) fn new(buf: &'a [u8], buf_i: usize) -> Self {
+    Self { buf, buf_i, maximum: buf_i }
+  }
 }
 
 impl<'a> Iterator for Labels<'a> {
@@ -174,7 +582,17 @@ impl<'a> Iterator for Labels<'a> {
     loop {
       match LabelType::read(self.buf, &mut self.buf_i).ok()? {
         LabelType::Pointer(ptr) => {
-          self.buf_i = ptr as usize;
+          let ptr = ptr as usize;
+
+          // Pointers can only point to previous occurences, otherwise stop
+          // since following them could loop forever.
+          if ptr >= self.maximum {
+            self.buf_i = self.buf.len();
+            return None
+          }
+
+          self.maximum = ptr;
+          self.buf_i = ptr;
           continue;
         },
         LabelType::Part(len) => {
@@ -202,18 +620,19 @@ pub(crate) struct Label<'a> {
 
 impl<'a> Label<'a> {
   #[inline]
-  pub fn as_bytes(&self) -> &[u8] {
+  pub fn as_bytes(&self) -> &'a [u8] {
     &self.buf[(self.buf_i + 1)..(self.buf_i + 1 + self.len())]
   }
 
+  /// Returns the label as a string, or `"\u{FFFD}"` if it is not valid UTF-8.
   #[inline]
-  pub fn as_str(&self) -> &str {
-    unsafe { str::from_utf8_unchecked(self.as_bytes()) }
+  pub fn as_str(&self) -> &'a str {
+    str::from_utf8(self.as_bytes()).unwrap_or("\u{FFFD}")
   }
 
   #[inline]
   pub fn len(&self) -> usize {
-    self.buf[self.buf_i] as usize
+    (self.buf[self.buf_i] & LEN_MASK) as usize
   }
 }
 
@@ -225,6 +644,32 @@ pub(crate) enum LabelType {
 
 const PTR_MASK: u8 = 0b11000000;
 const LEN_MASK: u8 = !PTR_MASK;
+const MAX_PTR: usize = 0x3FFF;
+
+/// Encode a pointer to offset `i`, if it is reachable by a pointer.
+pub(crate) fn pointer(i: usize) -> Option<[u8; 2]> {
+  if i > MAX_PTR {
+    return None
+  }
+
+  let [ptr_1, ptr_2] = (i as u16).to_be_bytes();
+  Some([ptr_1 | PTR_MASK, ptr_2])
+}
+
+/// Skip the name at `i` without following pointers.
+///
+/// Returns the offset and target of its pointer, if it is compressed.
+pub(crate) fn skip_name(buf: &[u8], i: &mut usize) -> Result<Option<(usize, usize)>, Error> {
+  loop {
+    let start = *i;
+
+    match LabelType::read(buf, i)? {
+      LabelType::Pointer(ptr) => return Ok(Some((start, ptr as usize))),
+      LabelType::Part(0) => return Ok(None),
+      LabelType::Part(_) => continue,
+    }
+  }
+}
 
 impl LabelType {
   /// Return whether a label was read and whether it was a pointer or a normal name part.
@@ -238,6 +683,7 @@ impl LabelType {
           return Ok(Self::Pointer(u16::from_be_bytes([ptr_or_len & LEN_MASK, ptr])))
         }
       } else {
+        // The reserved label types `0b01` and `0b10` are only rejected by `Name::validate`.
         let len = ptr_or_len & LEN_MASK;
 
         let j = *i + 1 + len as usize;
@@ -248,7 +694,7 @@ impl LabelType {
       }
     }
 
-    Err(Error::MessageTooShort)
+    Err(Error::MessageTooShort { offset: *i })
   }
 }
 
@@ -273,4 +719,59 @@ mod tests {
     assert_eq!(sub_sub_name.create_pointer(&name), None);
     assert_eq!(sub_sub_name.create_pointer(&sub_name), None);
   }
+
+  #[test]
+  fn test_name_read_too_long() {
+    let mut buf = [0; 257];
+    for i in 0..128 {
+      buf[i * 2] = 1;
+      buf[i * 2 + 1] = b'a';
+    }
+
+    assert!(matches!(Name::read(&buf, &mut 0), Err(Error::NameTooLong)));
+    assert!(Name::read(&buf[2..], &mut 0).is_ok());
+  }
+
+  #[test]
+  fn test_name_pointer_past_end() {
+    // The label at the pointer target extends past the pointer.
+    let mut buf = [0; HEADER_SIZE + 5];
+    buf[HEADER_SIZE..].copy_from_slice(&[3, b'a', 0b11000000, HEADER_SIZE as u8, 0]);
+    assert_eq!(Name::read(&buf, &mut (HEADER_SIZE + 2)).err(), Some(Error::Pointer { offset: HEADER_SIZE }));
+  }
+
+  #[test]
+  fn test_name_pointer_loop() {
+    let buf = [1, b'a', 0b11000000, 0];
+
+    assert_eq!(Name::read(&buf, &mut 0).err(), Some(Error::Pointer { offset: 2 }));
+    assert_eq!(Name::read(&buf, &mut 2).err(), Some(Error::Pointer { offset: 2 }));
+
+    let name = Name::from_bytes(&buf);
+    assert_eq!(name.label_count(), 1);
+    assert_eq!(name.to_string(), "a");
+
+    let name = Name { buf: &buf, start: 2 };
+    assert_eq!(name.labels().count(), 1);
+    assert_eq!(name.to_string(), "a");
+
+    let name = Name::from_bytes(&[0b11000000, 0]);
+    assert_eq!(name.labels().count(), 0);
+  }
+
+  #[test]
+  fn test_name_read_invalid_label() {
+    // Length claims more bytes than available.
+    let buf = [1, b'a', 5, b'a', b'b'];
+    assert_eq!(Name::read(&buf, &mut 0).err(), Some(Error::MessageTooShort { offset: 2 }));
+
+    // Reserved label types are only rejected when validating.
+    let buf = [1, b'a', 0b01000001, b'b', 0];
+    let name = Name::read(&buf, &mut 0).expect("reading name failed");
+    assert_eq!(name.to_string(), "a.b");
+    assert_eq!(name.validate().err(), Some(Error::InvalidLabel { offset: 2 }));
+    let buf = [0b10000001, b'a', 0];
+    let name = Name::read(&buf, &mut 0).expect("reading name failed");
+    assert_eq!(name.validate().err(), Some(Error::InvalidLabel { offset: 0 }));
+  }
 }
@@ -0,0 +1,47 @@
+use core::fmt;
+
+use heapless::Vec;
+
+use crate::Name;
+use crate::name::MAX_NAME_LEN;
+
+/// An owned, uncompressed DNS name.
+#[derive(Clone)]
+pub struct NameBuf {
+  buf: Vec<u8, MAX_NAME_LEN>,
+}
+
+impl NameBuf {
+  pub(crate) fn from_name(name: &Name<'_>) -> Self {
+    let mut buf = Vec::new();
+
+    // Names are limited to `MAX_NAME_LEN` bytes when parsed,
+    // so copying their labels always fits.
+    for label in name.labels() {
+      let _ = buf.push(label.len() as u8);
+      let _ = buf.extend_from_slice(label.as_bytes());
+    }
+    let _ = buf.push(0);
+
+    Self { buf }
+  }
+
+  #[inline]
+  pub fn as_name(&self) -> Name<'_> {
+    Name { buf: &self.buf, start: 0 }
+  }
+}
+
+impl fmt::Debug for NameBuf {
+  fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+    fmt.debug_tuple("NameBuf")
+      .field(&format_args!("{}", self.as_name()))
+      .finish()
+  }
+}
+
+impl fmt::Display for NameBuf {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    self.as_name().fmt(f)
+  }
+}
@@ -0,0 +1,78 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Answer, Header, Message, MessageRef, QueryClass, QueryKind, Question};
+
+/// An owned view of a [`Message`](struct.Message.html), used for serialization.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OwnedMessage {
+  pub header: Header,
+  pub questions: Vec<OwnedQuestion>,
+  pub answers: Vec<OwnedAnswer>,
+  pub name_servers: Vec<OwnedAnswer>,
+  pub additional_records: Vec<OwnedAnswer>,
+}
+
+/// An owned view of a [`Question`](struct.Question.html), used for serialization.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OwnedQuestion {
+  pub name: String,
+  pub kind: QueryKind,
+  pub class: QueryClass,
+  pub unicast_response: bool,
+}
+
+/// An owned view of an [`Answer`](struct.Answer.html), used for serialization.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OwnedAnswer {
+  pub name: String,
+  pub kind: QueryKind,
+  pub class: QueryClass,
+  pub ttl: u32,
+  pub rdata: Vec<u8>,
+  pub cache_flush: bool,
+}
+
+impl From<&Message<'_>> for OwnedMessage {
+  fn from(message: &Message<'_>) -> Self {
+    Self::from(&**message)
+  }
+}
+
+impl From<&MessageRef> for OwnedMessage {
+  fn from(message: &MessageRef) -> Self {
+    Self {
+      header: message.header().clone(),
+      questions: message.questions().map(|q| OwnedQuestion::from(&q)).collect(),
+      answers: message.answers().map(|a| OwnedAnswer::from(&a)).collect(),
+      name_servers: message.name_servers().map(|a| OwnedAnswer::from(&a)).collect(),
+      additional_records: message.additional_records().map(|a| OwnedAnswer::from(&a)).collect(),
+    }
+  }
+}
+
+impl From<&Question<'_>> for OwnedQuestion {
+  fn from(question: &Question<'_>) -> Self {
+    Self {
+      name: question.name().to_string(),
+      kind: *question.kind(),
+      class: *question.class(),
+      unicast_response: question.unicast_response(),
+    }
+  }
+}
+
+impl From<&Answer<'_>> for OwnedAnswer {
+  fn from(answer: &Answer<'_>) -> Self {
+    Self {
+      name: answer.name().to_string(),
+      kind: *answer.kind(),
+      class: *answer.class(),
+      ttl: answer.ttl(),
+      rdata: answer.rdata().to_vec(),
+      cache_flush: answer.cache_flush(),
+    }
+  }
+}
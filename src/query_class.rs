@@ -1,38 +1,56 @@
+use core::fmt;
 use core::mem::size_of;
+use core::str::FromStr;
 
-use crate::{Error};
+use crate::Error;
+use crate::query_kind::strip_prefix_ignore_ascii_case;
 
 /// The class of a DNS query.
 ///
 /// According to [RFC 1035 Section 3.2.4](https://tools.ietf.org/rfc/rfc1035#section-3.2.4).
-#[derive(Copy, Clone, Debug, PartialEq)]
-#[repr(u16)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum QueryClass {
   /// Internet
-  IN = 1,
+  IN,
   /// CSNET
-  CS = 2,
+  CS,
   /// CHAOS
-  CH = 3,
+  CH,
   /// Hesiod
-  HS = 4,
-  Reserved,
+  HS,
+  Reserved(u16),
 }
 
+/// Top bit of the class, used as a flag in multicast DNS.
+///
+/// According to [RFC 6762 Section 18.12](https://tools.ietf.org/rfc/rfc6762#section-18.12)
+/// and [RFC 6762 Section 18.13](https://tools.ietf.org/rfc/rfc6762#section-18.13).
+const MDNS_FLAG: u16 = 0x8000;
+
 impl QueryClass {
   pub(crate) fn read(buf: &[u8], i: &mut usize) -> Result<Self, Error> {
-    if *i + size_of::<QueryClass>() <= buf.len() {
+    if *i + size_of::<u16>() <= buf.len() {
       let query_class = u16::from_be_bytes([buf[*i], buf[*i + 1]]);
-      *i += size_of::<QueryClass>();
+      *i += size_of::<u16>();
 
       return Ok(query_class.into())
     }
 
-    Err(Error::MessageTooShort)
+    Err(Error::MessageTooShort { offset: *i })
   }
 
   pub fn to_be_bytes(self) -> [u8; 2] {
-    (self as u16).to_be_bytes()
+    u16::from(self).to_be_bytes()
+  }
+
+  pub(crate) fn split_mdns_flag(self) -> (Self, bool) {
+    let n = u16::from(self);
+    (Self::from(n & !MDNS_FLAG), n & MDNS_FLAG != 0)
+  }
+
+  pub(crate) fn to_be_bytes_with_mdns_flag(self, flag: bool) -> [u8; 2] {
+    (u16::from(self) | if flag { MDNS_FLAG } else { 0 }).to_be_bytes()
   }
 }
 
@@ -43,7 +61,55 @@ impl From<u16> for QueryClass {
       2 => Self::CS,
       3 => Self::CH,
       4 => Self::HS,
-      _ => Self::Reserved,
+      n => Self::Reserved(n),
+    }
+  }
+}
+
+impl From<QueryClass> for u16 {
+  fn from(class: QueryClass) -> Self {
+    match class {
+      QueryClass::IN => 1,
+      QueryClass::CS => 2,
+      QueryClass::CH => 3,
+      QueryClass::HS => 4,
+      QueryClass::Reserved(n) => n,
     }
   }
 }
+
+const CLASSES: [(&str, QueryClass); 4] = [
+  ("IN", QueryClass::IN),
+  ("CS", QueryClass::CS),
+  ("CH", QueryClass::CH),
+  ("HS", QueryClass::HS),
+];
+
+impl fmt::Display for QueryClass {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      QueryClass::IN => "IN",
+      QueryClass::CS => "CS",
+      QueryClass::CH => "CH",
+      QueryClass::HS => "HS",
+      QueryClass::Reserved(n) => return write!(f, "CLASS{}", n),
+    })
+  }
+}
+
+impl FromStr for QueryClass {
+  type Err = Error;
+
+  /// Parse a mnemonic like `IN` case-insensitively. Unknown classes can be
+  /// given in the generic `CLASS<n>` form according to [RFC 3597](https://tools.ietf.org/rfc/rfc3597).
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    if let Some(n) = strip_prefix_ignore_ascii_case(s, "CLASS") {
+      return n.parse::<u16>().map(Self::from).map_err(|_| Error::UnknownQueryClass)
+    }
+
+    CLASSES.iter()
+      .find(|(name, _)| name.eq_ignore_ascii_case(s))
+      .map(|&(_, class)| class)
+      .ok_or(Error::UnknownQueryClass)
+  }
+}
@@ -1,4 +1,6 @@
+use core::fmt;
 use core::mem::size_of;
+use core::str::FromStr;
 
 use crate::Error;
 
@@ -6,30 +8,97 @@ use crate::Error;
 ///
 /// According to [RFC 1035 Section 3.2.2](https://tools.ietf.org/rfc/rfc1035#section-3.2.2)
 /// and [RFC 1035 Section 3.2.3](https://tools.ietf.org/rfc/rfc1035#section-3.2.3).
-#[derive(Copy, Clone, Debug, PartialEq)]
-#[repr(u16)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum QueryKind {
-  A = 1,
-  NS = 2,
-  MD = 3,
-  MF = 4,
-  CNAME = 5,
-  SOA = 6,
-  MB = 7,
-  MG = 8,
-  MR = 9,
-  NULL = 10,
-  WKS = 11,
-  PTR = 12,
-  HINFO = 13,
-  MINFO = 14,
-  MX = 15,
-  TXT = 16,
-  AXFR = 252,
-  MAILB = 253,
-  MAILA = 254,
-  ALL = 255,
-  Reserved,
+  A,
+  NS,
+  MD,
+  MF,
+  CNAME,
+  SOA,
+  MB,
+  MG,
+  MR,
+  NULL,
+  WKS,
+  PTR,
+  HINFO,
+  MINFO,
+  MX,
+  TXT,
+  /// [RFC 3596](https://tools.ietf.org/rfc/rfc3596)
+  AAAA,
+  /// [RFC 1876](https://tools.ietf.org/rfc/rfc1876)
+  LOC,
+  /// [RFC 2782](https://tools.ietf.org/rfc/rfc2782)
+  SRV,
+  /// [RFC 3403](https://tools.ietf.org/rfc/rfc3403)
+  NAPTR,
+  /// [RFC 6672](https://tools.ietf.org/rfc/rfc6672)
+  DNAME,
+  /// [RFC 6891](https://tools.ietf.org/rfc/rfc6891)
+  OPT,
+  /// [RFC 4034](https://tools.ietf.org/rfc/rfc4034)
+  DS,
+  /// [RFC 4255](https://tools.ietf.org/rfc/rfc4255)
+  SSHFP,
+  /// [RFC 4034](https://tools.ietf.org/rfc/rfc4034)
+  RRSIG,
+  /// [RFC 6698](https://tools.ietf.org/rfc/rfc6698)
+  TLSA,
+  /// [RFC 9460](https://tools.ietf.org/rfc/rfc9460)
+  SVCB,
+  /// [RFC 9460](https://tools.ietf.org/rfc/rfc9460)
+  HTTPS,
+  AXFR,
+  MAILB,
+  MAILA,
+  ALL,
+  /// [RFC 8659](https://tools.ietf.org/rfc/rfc8659)
+  CAA,
+  Reserved(u16),
+}
+
+impl From<QueryKind> for u16 {
+  fn from(kind: QueryKind) -> Self {
+    match kind {
+      QueryKind::A => 1,
+      QueryKind::NS => 2,
+      QueryKind::MD => 3,
+      QueryKind::MF => 4,
+      QueryKind::CNAME => 5,
+      QueryKind::SOA => 6,
+      QueryKind::MB => 7,
+      QueryKind::MG => 8,
+      QueryKind::MR => 9,
+      QueryKind::NULL => 10,
+      QueryKind::WKS => 11,
+      QueryKind::PTR => 12,
+      QueryKind::HINFO => 13,
+      QueryKind::MINFO => 14,
+      QueryKind::MX => 15,
+      QueryKind::TXT => 16,
+      QueryKind::AAAA => 28,
+      QueryKind::LOC => 29,
+      QueryKind::SRV => 33,
+      QueryKind::NAPTR => 35,
+      QueryKind::DNAME => 39,
+      QueryKind::OPT => 41,
+      QueryKind::DS => 43,
+      QueryKind::SSHFP => 44,
+      QueryKind::RRSIG => 46,
+      QueryKind::TLSA => 52,
+      QueryKind::SVCB => 64,
+      QueryKind::HTTPS => 65,
+      QueryKind::AXFR => 252,
+      QueryKind::MAILB => 253,
+      QueryKind::MAILA => 254,
+      QueryKind::ALL => 255,
+      QueryKind::CAA => 257,
+      QueryKind::Reserved(n) => n,
+    }
+  }
 }
 
 impl From<u16> for QueryKind {
@@ -51,28 +120,159 @@ impl From<u16> for QueryKind {
       14 => Self::MINFO,
       15 => Self::MX,
       16 => Self::TXT,
+      28 => Self::AAAA,
+      29 => Self::LOC,
+      33 => Self::SRV,
+      35 => Self::NAPTR,
+      39 => Self::DNAME,
+      41 => Self::OPT,
+      43 => Self::DS,
+      44 => Self::SSHFP,
+      46 => Self::RRSIG,
+      52 => Self::TLSA,
+      64 => Self::SVCB,
+      65 => Self::HTTPS,
       252 => Self::AXFR,
-      253 => Self::MAILA,
-      254 => Self::MAILB,
+      253 => Self::MAILB,
+      254 => Self::MAILA,
       255 => Self::ALL,
-      _ => Self::Reserved,
+      257 => Self::CAA,
+      n => Self::Reserved(n),
     }
   }
 }
 
+const KINDS: [(&str, QueryKind); 35] = [
+  ("A", QueryKind::A),
+  ("NS", QueryKind::NS),
+  ("MD", QueryKind::MD),
+  ("MF", QueryKind::MF),
+  ("CNAME", QueryKind::CNAME),
+  ("SOA", QueryKind::SOA),
+  ("MB", QueryKind::MB),
+  ("MG", QueryKind::MG),
+  ("MR", QueryKind::MR),
+  ("NULL", QueryKind::NULL),
+  ("WKS", QueryKind::WKS),
+  ("PTR", QueryKind::PTR),
+  ("HINFO", QueryKind::HINFO),
+  ("MINFO", QueryKind::MINFO),
+  ("MX", QueryKind::MX),
+  ("TXT", QueryKind::TXT),
+  ("AAAA", QueryKind::AAAA),
+  ("LOC", QueryKind::LOC),
+  ("SRV", QueryKind::SRV),
+  ("NAPTR", QueryKind::NAPTR),
+  ("DNAME", QueryKind::DNAME),
+  ("OPT", QueryKind::OPT),
+  ("DS", QueryKind::DS),
+  ("SSHFP", QueryKind::SSHFP),
+  ("RRSIG", QueryKind::RRSIG),
+  ("TLSA", QueryKind::TLSA),
+  ("SVCB", QueryKind::SVCB),
+  ("HTTPS", QueryKind::HTTPS),
+  ("AXFR", QueryKind::AXFR),
+  ("MAILB", QueryKind::MAILB),
+  ("MAILA", QueryKind::MAILA),
+  ("ANY", QueryKind::ALL),
+  ("ALL", QueryKind::ALL),
+  ("*", QueryKind::ALL),
+  ("CAA", QueryKind::CAA),
+];
+
+impl fmt::Display for QueryKind {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      QueryKind::A => "A",
+      QueryKind::NS => "NS",
+      QueryKind::MD => "MD",
+      QueryKind::MF => "MF",
+      QueryKind::CNAME => "CNAME",
+      QueryKind::SOA => "SOA",
+      QueryKind::MB => "MB",
+      QueryKind::MG => "MG",
+      QueryKind::MR => "MR",
+      QueryKind::NULL => "NULL",
+      QueryKind::WKS => "WKS",
+      QueryKind::PTR => "PTR",
+      QueryKind::HINFO => "HINFO",
+      QueryKind::MINFO => "MINFO",
+      QueryKind::MX => "MX",
+      QueryKind::TXT => "TXT",
+      QueryKind::AAAA => "AAAA",
+      QueryKind::LOC => "LOC",
+      QueryKind::SRV => "SRV",
+      QueryKind::NAPTR => "NAPTR",
+      QueryKind::DNAME => "DNAME",
+      QueryKind::OPT => "OPT",
+      QueryKind::DS => "DS",
+      QueryKind::SSHFP => "SSHFP",
+      QueryKind::RRSIG => "RRSIG",
+      QueryKind::TLSA => "TLSA",
+      QueryKind::SVCB => "SVCB",
+      QueryKind::HTTPS => "HTTPS",
+      QueryKind::AXFR => "AXFR",
+      QueryKind::MAILB => "MAILB",
+      QueryKind::MAILA => "MAILA",
+      QueryKind::ALL => "ANY",
+      QueryKind::CAA => "CAA",
+      QueryKind::Reserved(n) => return write!(f, "TYPE{}", n),
+    })
+  }
+}
+
+impl FromStr for QueryKind {
+  type Err = Error;
+
+  /// Parse a mnemonic like `AAAA` case-insensitively. Unknown types can be
+  /// given in the generic `TYPE<n>` form according to [RFC 3597](https://tools.ietf.org/rfc/rfc3597).
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    if let Some(n) = strip_prefix_ignore_ascii_case(s, "TYPE") {
+      return n.parse::<u16>().map(Self::from).map_err(|_| Error::UnknownQueryKind)
+    }
+
+    KINDS.iter()
+      .find(|(name, _)| name.eq_ignore_ascii_case(s))
+      .map(|&(_, kind)| kind)
+      .ok_or(Error::UnknownQueryKind)
+  }
+}
+
 impl QueryKind {
+  /// Whether this kind is a query type only valid in questions.
+  ///
+  /// According to [RFC 6895 Section 3.1](https://tools.ietf.org/rfc/rfc6895#section-3.1).
+  #[inline]
+  pub fn is_meta(&self) -> bool {
+    matches!(self, Self::AXFR | Self::MAILB | Self::MAILA | Self::ALL)
+  }
+
+  /// Whether this kind is a pseudo-record type only valid in the additional section.
+  #[inline]
+  pub fn is_pseudo(&self) -> bool {
+    matches!(self, Self::OPT)
+  }
+
   pub(crate) fn read(buf: &[u8], i: &mut usize) -> Result<Self, Error> {
-    if *i + size_of::<QueryKind>() <= buf.len() {
+    if *i + size_of::<u16>() <= buf.len() {
       let query_kind = u16::from_be_bytes([buf[*i], buf[*i + 1]]);
-      *i += size_of::<QueryKind>();
+      *i += size_of::<u16>();
 
       return Ok(query_kind.into())
     }
 
-    Err(Error::MessageTooShort)
+    Err(Error::MessageTooShort { offset: *i })
   }
 
   pub(crate) fn to_be_bytes(self) -> [u8; 2] {
-    (self as u16).to_be_bytes()
+    u16::from(self).to_be_bytes()
+  }
+}
+
+pub(crate) fn strip_prefix_ignore_ascii_case<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+  if s.get(..prefix.len())?.eq_ignore_ascii_case(prefix) {
+    Some(&s[prefix.len()..])
+  } else {
+    None
   }
 }
@@ -1,13 +1,17 @@
 use core::fmt;
+use core::mem::size_of;
 
 use crate::{Error, Name, QueryKind, QueryClass};
+use crate::name::push_bytes;
 
 /// A DNS question.
+#[derive(Clone)]
 #[repr(C)]
 pub struct Question<'a> {
   pub(crate) name: Name<'a>,
   pub(crate) kind: QueryKind,
   pub(crate) class: QueryClass,
+  pub(crate) unicast_response: bool,
 }
 
 impl fmt::Debug for Question<'_> {
@@ -16,18 +20,23 @@ impl fmt::Debug for Question<'_> {
       .field("name", &self.name())
       .field("kind", &self.kind())
       .field("class", &self.class())
+      .field("unicast_response", &self.unicast_response())
       .finish()
   }
 }
 
 impl<'a> Question<'a> {
+  #[inline]
+  pub fn new(name: Name<'a>, kind: QueryKind, class: QueryClass) -> Self {
+    Self { name, kind, class, unicast_response: false }
+  }
+
   pub(crate) fn read(buf: &'a [u8], i: &'_ mut usize) -> Result<Self, Error> {
     let mut j = *i;
-    let question = Self {
-      name:  Name::read(buf, &mut j)?,
-      kind:  QueryKind::read(buf, &mut j)?,
-      class: QueryClass::read(buf, &mut j)?,
-    };
+    let name = Name::read(buf, &mut j)?;
+    let kind = QueryKind::read(buf, &mut j)?;
+    let (class, unicast_response) = QueryClass::read(buf, &mut j)?.split_mdns_flag();
+    let question = Self { name, kind, class, unicast_response };
     *i = j;
 
     Ok(question)
@@ -47,10 +56,40 @@ impl<'a> Question<'a> {
   pub fn class(&self) -> &QueryClass {
     &self.class
   }
+
+  /// Whether a unicast response is requested, as used in multicast DNS.
+  ///
+  /// This is the top bit of the class, which is not included in [`class`](#method.class).
+  #[inline]
+  pub fn unicast_response(&self) -> bool {
+    self.unicast_response
+  }
+
+  #[inline]
+  pub fn set_unicast_response(&mut self, unicast_response: bool) {
+    self.unicast_response = unicast_response;
+  }
+
+  /// Returns the length of this question on the wire without name compression.
+  ///
+  /// This is an upper bound for the space needed to add it to a message.
+  pub fn wire_len(&self) -> usize {
+    self.name.byte_len() + size_of::<u16>() + size_of::<u16>()
+  }
+
+  /// Write the wire form of this question into `out` without name compression.
+  ///
+  /// Returns the number of bytes written.
+  pub fn write(&self, out: &mut [u8]) -> Result<usize, Error> {
+    let mut len = self.name.write(out)?;
+    push_bytes(out, &mut len, &self.kind.to_be_bytes())?;
+    push_bytes(out, &mut len, &self.class.to_be_bytes_with_mdns_flag(self.unicast_response))?;
+    Ok(len)
+  }
 }
 
 /// Iterator over [`Question`](struct.Question.html)s contained in a [`Message`](struct.Message.html).
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Questions<'a> {
   pub(crate) question_count: usize,
   pub(crate) current_question: usize,
@@ -75,4 +114,11 @@ impl<'a> Iterator for Questions<'a> {
 
     Some(question)
   }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let remaining = self.question_count.saturating_sub(self.current_question);
+    (remaining, Some(remaining))
+  }
 }
+
+impl ExactSizeIterator for Questions<'_> {}
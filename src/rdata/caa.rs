@@ -0,0 +1,65 @@
+use core::str;
+
+use crate::{Answer, QueryKind};
+
+/// The data of a `CAA` record.
+///
+/// According to [RFC 8659](https://tools.ietf.org/rfc/rfc8659).
+#[derive(Debug, Clone)]
+pub struct Caa<'a> {
+  flags: u8,
+  tag: &'a str,
+  value: &'a [u8],
+}
+
+impl<'a> Caa<'a> {
+  #[inline]
+  pub fn flags(&self) -> u8 {
+    self.flags
+  }
+
+  /// Whether the issuer critical flag is set.
+  #[inline]
+  pub fn critical(&self) -> bool {
+    (self.flags & 0b10000000) != 0
+  }
+
+  #[inline]
+  pub fn tag(&self) -> &'a str {
+    self.tag
+  }
+
+  #[inline]
+  pub fn value(&self) -> &'a [u8] {
+    self.value
+  }
+}
+
+impl<'a> Answer<'a> {
+  /// Returns the data contained in a `CAA` record.
+  pub fn as_caa(&self) -> Option<Caa<'a>> {
+    if self.kind != QueryKind::CAA {
+      return None
+    }
+
+    let (&flags, rest) = self.rdata.split_first()?;
+    let (&tag_len, rest) = rest.split_first()?;
+
+    let tag_len = tag_len as usize;
+    if tag_len == 0 || tag_len > rest.len() {
+      return None
+    }
+
+    let (tag, value) = rest.split_at(tag_len);
+
+    if !tag.iter().all(u8::is_ascii_alphanumeric) {
+      return None
+    }
+
+    Some(Caa {
+      flags,
+      tag: str::from_utf8(tag).ok()?,
+      value,
+    })
+  }
+}
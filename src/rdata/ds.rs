@@ -0,0 +1,53 @@
+use crate::{Answer, QueryKind};
+
+/// The data of a `DS` record.
+///
+/// According to [RFC 4034](https://tools.ietf.org/rfc/rfc4034#section-5.1).
+#[derive(Debug, Clone)]
+pub struct Ds<'a> {
+  key_tag: u16,
+  algorithm: u8,
+  digest_type: u8,
+  digest: &'a [u8],
+}
+
+impl<'a> Ds<'a> {
+  #[inline]
+  pub fn key_tag(&self) -> u16 {
+    self.key_tag
+  }
+
+  #[inline]
+  pub fn algorithm(&self) -> u8 {
+    self.algorithm
+  }
+
+  #[inline]
+  pub fn digest_type(&self) -> u8 {
+    self.digest_type
+  }
+
+  #[inline]
+  pub fn digest(&self) -> &'a [u8] {
+    self.digest
+  }
+}
+
+impl<'a> Answer<'a> {
+  /// Returns the data contained in a `DS` record.
+  pub fn as_ds(&self) -> Option<Ds<'a>> {
+    if self.kind != QueryKind::DS {
+      return None
+    }
+
+    let key_tag = self.rdata_u16(0)?;
+    let (&algorithm, rest) = self.rdata[2..].split_first()?;
+    let (&digest_type, digest) = rest.split_first()?;
+
+    if digest.is_empty() {
+      return None
+    }
+
+    Some(Ds { key_tag, algorithm, digest_type, digest })
+  }
+}
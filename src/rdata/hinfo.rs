@@ -0,0 +1,24 @@
+use core::str;
+
+use crate::{Answer, QueryKind};
+
+use super::read_character_string;
+
+impl<'a> Answer<'a> {
+  /// Returns the CPU and OS contained in an `HINFO` record.
+  pub fn as_hinfo(&self) -> Option<(&'a str, &'a str)> {
+    if self.kind != QueryKind::HINFO {
+      return None
+    }
+
+    let mut i = 0;
+    let cpu = read_character_string(self.rdata, &mut i)?;
+    let os = read_character_string(self.rdata, &mut i)?;
+
+    if i != self.rdata.len() {
+      return None
+    }
+
+    Some((str::from_utf8(cpu).ok()?, str::from_utf8(os).ok()?))
+  }
+}
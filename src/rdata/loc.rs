@@ -0,0 +1,121 @@
+use core::convert::TryFrom;
+
+use crate::{Answer, QueryKind};
+
+/// Latitude and longitude of zero are encoded as 2<sup>31</sup>.
+const ORIGIN: i64 = 1 << 31;
+/// Altitudes are offset by 100,000 m below the WGS 84 reference spheroid.
+const ALTITUDE_BASE: i64 = 10_000_000;
+
+/// The data of a `LOC` record.
+///
+/// According to [RFC 1876](https://tools.ietf.org/rfc/rfc1876#section-2).
+/// The getters return the encoded values, the `*_cm` and `*_mas` methods decode them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Loc {
+  version: u8,
+  size: u8,
+  horizontal_precision: u8,
+  vertical_precision: u8,
+  latitude: u32,
+  longitude: u32,
+  altitude: u32,
+}
+
+/// Decode a size or precision given as a mantissa in the upper and
+/// a power of 10 in the lower four bits.
+fn decode_size(size: u8) -> u64 {
+  u64::from(size >> 4) * 10u64.pow(u32::from(size & 0x0f))
+}
+
+impl Loc {
+  #[inline]
+  pub fn version(&self) -> u8 {
+    self.version
+  }
+
+  #[inline]
+  pub fn size(&self) -> u8 {
+    self.size
+  }
+
+  #[inline]
+  pub fn horizontal_precision(&self) -> u8 {
+    self.horizontal_precision
+  }
+
+  #[inline]
+  pub fn vertical_precision(&self) -> u8 {
+    self.vertical_precision
+  }
+
+  #[inline]
+  pub fn latitude(&self) -> u32 {
+    self.latitude
+  }
+
+  #[inline]
+  pub fn longitude(&self) -> u32 {
+    self.longitude
+  }
+
+  #[inline]
+  pub fn altitude(&self) -> u32 {
+    self.altitude
+  }
+
+  /// The diameter of the sphere enclosing the location in centimeters.
+  pub fn size_cm(&self) -> u64 {
+    decode_size(self.size)
+  }
+
+  pub fn horizontal_precision_cm(&self) -> u64 {
+    decode_size(self.horizontal_precision)
+  }
+
+  pub fn vertical_precision_cm(&self) -> u64 {
+    decode_size(self.vertical_precision)
+  }
+
+  /// The latitude in thousandths of an arc second, positive values are north of the equator.
+  pub fn latitude_mas(&self) -> i64 {
+    i64::from(self.latitude) - ORIGIN
+  }
+
+  /// The longitude in thousandths of an arc second, positive values are east of the prime meridian.
+  pub fn longitude_mas(&self) -> i64 {
+    i64::from(self.longitude) - ORIGIN
+  }
+
+  /// The altitude in centimeters above the WGS 84 reference spheroid.
+  pub fn altitude_cm(&self) -> i64 {
+    i64::from(self.altitude) - ALTITUDE_BASE
+  }
+}
+
+impl Answer<'_> {
+  /// Returns the data contained in a `LOC` record.
+  ///
+  /// Only version 0 is supported.
+  pub fn as_loc(&self) -> Option<Loc> {
+    if self.kind != QueryKind::LOC {
+      return None
+    }
+
+    let rdata = <&[u8; 16]>::try_from(self.rdata).ok()?;
+
+    if rdata[0] != 0 {
+      return None
+    }
+
+    Some(Loc {
+      version:              rdata[0],
+      size:                 rdata[1],
+      horizontal_precision: rdata[2],
+      vertical_precision:   rdata[3],
+      latitude:             self.rdata_u32(4)?,
+      longitude:            self.rdata_u32(8)?,
+      altitude:             self.rdata_u32(12)?,
+    })
+  }
+}
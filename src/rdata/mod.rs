@@ -0,0 +1,169 @@
+use core::mem::size_of;
+use core::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::{Answer, Name, QueryKind};
+
+mod caa;
+pub use caa::Caa;
+
+mod ds;
+pub use ds::Ds;
+
+mod hinfo;
+
+mod loc;
+pub use loc::Loc;
+
+mod naptr;
+pub use naptr::Naptr;
+
+mod opt;
+pub use opt::{Cookie, EcsOption, EdnsOptions, Opt, OptBuilder};
+
+mod rrsig;
+pub use rrsig::Rrsig;
+
+mod soa;
+pub use soa::{Serial, Soa};
+
+mod srv;
+pub use srv::Srv;
+
+mod sshfp;
+pub use sshfp::Sshfp;
+
+mod svcb;
+pub use svcb::{SvcParams, Svcb};
+
+mod tlsa;
+pub use tlsa::Tlsa;
+
+mod txt;
+pub use txt::TxtStrings;
+
+/// Read a length-prefixed character-string starting at `i`.
+fn read_character_string<'a>(rdata: &'a [u8], i: &mut usize) -> Option<&'a [u8]> {
+  let len = *rdata.get(*i)? as usize;
+  let string = rdata.get((*i + 1)..(*i + 1 + len))?;
+  *i += 1 + len;
+  Some(string)
+}
+
+/// Iterator over 16-bit keys and length-prefixed values, as used by
+/// [`EdnsOptions`](struct.EdnsOptions.html) and [`SvcParams`](struct.SvcParams.html).
+#[derive(Debug, Clone)]
+pub(crate) struct Tlvs<'a> {
+  buf: &'a [u8],
+  buf_i: usize,
+}
+
+impl<'a> Tlvs<'a> {
+  pub(crate) fn new(buf: &'a [u8]) -> Self {
+    Self { buf, buf_i: 0 }
+  }
+}
+
+impl<'a> Iterator for Tlvs<'a> {
+  type Item = (u16, &'a [u8]);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let start = self.buf_i + size_of::<u16>() + size_of::<u16>();
+
+    if let Some(header) = self.buf.get(self.buf_i..start) {
+      let key = u16::from_be_bytes([header[0], header[1]]);
+      let len = u16::from_be_bytes([header[2], header[3]]) as usize;
+
+      if let Some(value) = self.buf.get(start..(start + len)) {
+        self.buf_i = start + len;
+        return Some((key, value))
+      }
+    }
+
+    self.buf_i = self.buf.len();
+    None
+  }
+}
+
+/// Returns the offset of the first name and the number of consecutive names
+/// contained in RDATA of the given kind.
+///
+/// Only the RDATA itself is inspected, pointers are not followed.
+pub(crate) fn rdata_names(kind: QueryKind, rdata: &[u8]) -> Option<(usize, usize)> {
+  match kind {
+    QueryKind::CNAME | QueryKind::NS | QueryKind::PTR | QueryKind::DNAME |
+    QueryKind::MB | QueryKind::MD | QueryKind::MF | QueryKind::MG | QueryKind::MR => Some((0, 1)),
+    QueryKind::SOA | QueryKind::MINFO => Some((0, 2)),
+    QueryKind::MX | QueryKind::SVCB | QueryKind::HTTPS => Some((2, 1)),
+    QueryKind::SRV => Some((6, 1)),
+    QueryKind::RRSIG => Some((18, 1)),
+    QueryKind::NAPTR => {
+      let mut i = 4;
+      read_character_string(rdata, &mut i)?;
+      read_character_string(rdata, &mut i)?;
+      read_character_string(rdata, &mut i)?;
+      Some((i, 1))
+    },
+    _ => None,
+  }
+}
+
+/// The RDATA of an [`Answer`](struct.Answer.html), parsed according to its kind.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum Rdata<'a> {
+  A(Ipv4Addr),
+  Aaaa(Ipv6Addr),
+  Cname(Name<'a>),
+  Ns(Name<'a>),
+  Ptr(Name<'a>),
+  Mx { preference: u16, exchange: Name<'a> },
+  Hinfo { cpu: &'a str, os: &'a str },
+  Loc(Loc),
+  Txt(TxtStrings<'a>),
+  Soa(Soa<'a>),
+  Srv(Srv<'a>),
+  Naptr(Naptr<'a>),
+  Dname(Name<'a>),
+  Caa(Caa<'a>),
+  Opt(Opt<'a>),
+  Ds(Ds<'a>),
+  Sshfp(Sshfp<'a>),
+  Rrsig(Rrsig<'a>),
+  Tlsa(Tlsa<'a>),
+  Svcb(Svcb<'a>),
+  Https(Svcb<'a>),
+  /// RDATA of an unsupported kind or which could not be parsed.
+  Unknown(&'a [u8]),
+}
+
+impl<'a> Answer<'a> {
+  /// Parse the RDATA according to the kind of this answer.
+  pub fn parse_rdata(&self) -> Rdata<'a> {
+    let rdata = match self.kind {
+      QueryKind::A => self.as_ipv4().map(Rdata::A),
+      QueryKind::AAAA => self.as_ipv6().map(Rdata::Aaaa),
+      QueryKind::CNAME => self.rdata_name().map(Rdata::Cname),
+      QueryKind::NS => self.rdata_name().map(Rdata::Ns),
+      QueryKind::PTR => self.rdata_name().map(Rdata::Ptr),
+      QueryKind::MX => self.as_mx().map(|(preference, exchange)| Rdata::Mx { preference, exchange }),
+      QueryKind::HINFO => self.as_hinfo().map(|(cpu, os)| Rdata::Hinfo { cpu, os }),
+      QueryKind::TXT => Some(Rdata::Txt(self.txt_strings())),
+      QueryKind::SOA => self.as_soa().map(Rdata::Soa),
+      QueryKind::LOC => self.as_loc().map(Rdata::Loc),
+      QueryKind::SRV => self.as_srv().map(Rdata::Srv),
+      QueryKind::NAPTR => self.as_naptr().map(Rdata::Naptr),
+      QueryKind::DNAME => self.as_dname().map(Rdata::Dname),
+      QueryKind::CAA => self.as_caa().map(Rdata::Caa),
+      QueryKind::OPT => self.as_opt().map(Rdata::Opt),
+      QueryKind::DS => self.as_ds().map(Rdata::Ds),
+      QueryKind::SSHFP => self.as_sshfp().map(Rdata::Sshfp),
+      QueryKind::RRSIG => self.as_rrsig().map(Rdata::Rrsig),
+      QueryKind::TLSA => self.as_tlsa().map(Rdata::Tlsa),
+      QueryKind::SVCB => self.as_svcb().map(Rdata::Svcb),
+      QueryKind::HTTPS => self.as_svcb().map(Rdata::Https),
+      _ => None,
+    };
+
+    rdata.unwrap_or(Rdata::Unknown(self.rdata))
+  }
+}
@@ -0,0 +1,77 @@
+use crate::{Answer, Name, QueryKind};
+
+use super::read_character_string;
+
+/// The data of a `NAPTR` record.
+///
+/// According to [RFC 3403](https://tools.ietf.org/rfc/rfc3403#section-4.1).
+#[derive(Debug, Clone)]
+pub struct Naptr<'a> {
+  order: u16,
+  preference: u16,
+  flags: &'a [u8],
+  services: &'a [u8],
+  regexp: &'a [u8],
+  replacement: Name<'a>,
+}
+
+impl<'a> Naptr<'a> {
+  #[inline]
+  pub fn order(&self) -> u16 {
+    self.order
+  }
+
+  #[inline]
+  pub fn preference(&self) -> u16 {
+    self.preference
+  }
+
+  #[inline]
+  pub fn flags(&self) -> &'a [u8] {
+    self.flags
+  }
+
+  #[inline]
+  pub fn services(&self) -> &'a [u8] {
+    self.services
+  }
+
+  #[inline]
+  pub fn regexp(&self) -> &'a [u8] {
+    self.regexp
+  }
+
+  #[inline]
+  pub fn replacement(&self) -> &Name<'a> {
+    &self.replacement
+  }
+}
+
+impl<'a> Answer<'a> {
+  /// Returns the data contained in a `NAPTR` record.
+  pub fn as_naptr(&self) -> Option<Naptr<'a>> {
+    if self.kind != QueryKind::NAPTR {
+      return None
+    }
+
+    let mut i = 4;
+    let flags = read_character_string(self.rdata, &mut i)?;
+    let services = read_character_string(self.rdata, &mut i)?;
+    let regexp = read_character_string(self.rdata, &mut i)?;
+
+    let (replacement, end) = self.read_rdata_name(i)?;
+
+    if end != self.rdata.len() {
+      return None
+    }
+
+    Some(Naptr {
+      order:      self.rdata_u16(0)?,
+      preference: self.rdata_u16(2)?,
+      flags,
+      services,
+      regexp,
+      replacement,
+    })
+  }
+}
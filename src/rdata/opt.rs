@@ -0,0 +1,326 @@
+use core::mem::size_of;
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use crate::{Answer, Error, Name, QueryClass, QueryKind};
+
+use super::Tlvs;
+
+const MAX_OPTIONS_LEN: usize = 128;
+
+const CLIENT_SUBNET: u16 = 8;
+const FAMILY_IPV4: u16 = 1;
+const FAMILY_IPV6: u16 = 2;
+
+const COOKIE: u16 = 10;
+const CLIENT_COOKIE_LEN: usize = 8;
+const MIN_SERVER_COOKIE_LEN: usize = 8;
+const MAX_SERVER_COOKIE_LEN: usize = 32;
+
+/// The data of an `OPT` pseudo-record.
+///
+/// According to [RFC 6891](https://tools.ietf.org/rfc/rfc6891#section-6.1).
+#[derive(Debug, Clone)]
+pub struct Opt<'a> {
+  udp_payload_size: u16,
+  ttl: u32,
+  rdata: &'a [u8],
+}
+
+impl<'a> Opt<'a> {
+  /// Returns the maximum UDP payload size of the sender.
+  #[inline]
+  pub fn udp_payload_size(&self) -> u16 {
+    self.udp_payload_size
+  }
+
+  /// Returns the upper 8 bits of the extended response code.
+  #[inline]
+  pub fn extended_response_code(&self) -> u8 {
+    (self.ttl >> 24) as u8
+  }
+
+  #[inline]
+  pub fn version(&self) -> u8 {
+    (self.ttl >> 16) as u8
+  }
+
+  /// Returns whether the DNSSEC OK bit is set.
+  #[inline]
+  pub fn dnssec_ok(&self) -> bool {
+    self.ttl & 0x8000 != 0
+  }
+
+  /// Returns an iterator over the options contained in this record.
+  #[inline]
+  pub fn options(&self) -> EdnsOptions<'a> {
+    EdnsOptions(Tlvs::new(self.rdata))
+  }
+
+  /// Returns the first valid cookie option contained in this record.
+  pub fn cookie(&self) -> Option<Cookie<'a>> {
+    self.options().filter(|&(code, _)| code == COOKIE).find_map(|(_, data)| {
+      let (client, server) = (data.get(..CLIENT_COOKIE_LEN)?, &data[CLIENT_COOKIE_LEN..]);
+
+      let server = match server.len() {
+        0 => None,
+        MIN_SERVER_COOKIE_LEN..=MAX_SERVER_COOKIE_LEN => Some(server),
+        _ => return None,
+      };
+
+      let mut client_cookie = [0; CLIENT_COOKIE_LEN];
+      client_cookie.copy_from_slice(client);
+
+      Some(Cookie { client: client_cookie, server })
+    })
+  }
+
+  /// Returns the first valid client subnet option contained in this record.
+  pub fn ecs(&self) -> Option<EcsOption> {
+    self.options().filter(|&(code, _)| code == CLIENT_SUBNET).find_map(|(_, data)| EcsOption::read(data))
+  }
+
+  #[inline]
+  pub fn builder() -> OptBuilder {
+    OptBuilder::new()
+  }
+}
+
+/// A DNS cookie option.
+///
+/// According to [RFC 7873](https://tools.ietf.org/rfc/rfc7873#section-4).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cookie<'a> {
+  client: [u8; CLIENT_COOKIE_LEN],
+  server: Option<&'a [u8]>,
+}
+
+impl<'a> Cookie<'a> {
+  #[inline]
+  pub fn client(&self) -> [u8; CLIENT_COOKIE_LEN] {
+    self.client
+  }
+
+  #[inline]
+  pub fn server(&self) -> Option<&'a [u8]> {
+    self.server
+  }
+}
+
+/// An EDNS client subnet option.
+///
+/// According to [RFC 7871](https://tools.ietf.org/rfc/rfc7871#section-6).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EcsOption {
+  address: IpAddr,
+  source_prefix_len: u8,
+  scope_prefix_len: u8,
+}
+
+impl EcsOption {
+  /// Create a new client subnet option. Address bits beyond the source prefix length are cleared.
+  pub fn new(address: IpAddr, source_prefix_len: u8, scope_prefix_len: u8) -> Self {
+    let (address, source_prefix_len) = match address {
+      IpAddr::V4(addr) => {
+        let len = source_prefix_len.min(32);
+        let mask = u32::MAX.checked_shl(32 - len as u32).unwrap_or(0);
+        (IpAddr::V4(Ipv4Addr::from(u32::from(addr) & mask)), len)
+      },
+      IpAddr::V6(addr) => {
+        let len = source_prefix_len.min(128);
+        let mask = u128::MAX.checked_shl(128 - len as u32).unwrap_or(0);
+        (IpAddr::V6(Ipv6Addr::from(u128::from(addr) & mask)), len)
+      },
+    };
+
+    Self { address, source_prefix_len, scope_prefix_len }
+  }
+
+  #[inline]
+  pub fn family(&self) -> u16 {
+    match self.address {
+      IpAddr::V4(_) => FAMILY_IPV4,
+      IpAddr::V6(_) => FAMILY_IPV6,
+    }
+  }
+
+  #[inline]
+  pub fn address(&self) -> IpAddr {
+    self.address
+  }
+
+  #[inline]
+  pub fn source_prefix_len(&self) -> u8 {
+    self.source_prefix_len
+  }
+
+  #[inline]
+  pub fn scope_prefix_len(&self) -> u8 {
+    self.scope_prefix_len
+  }
+
+  fn address_len(&self) -> usize {
+    (self.source_prefix_len as usize).div_ceil(8)
+  }
+
+  fn read(data: &[u8]) -> Option<Self> {
+    let family = u16::from_be_bytes([*data.first()?, *data.get(1)?]);
+    let source_prefix_len = *data.get(2)?;
+    let scope_prefix_len = *data.get(3)?;
+    let address = &data[4..];
+
+    if address.len() != (source_prefix_len as usize).div_ceil(8) {
+      return None
+    }
+
+    let address = match family {
+      FAMILY_IPV4 => {
+        let mut octets = [0; 4];
+        octets.get_mut(..address.len())?.copy_from_slice(address);
+        IpAddr::V4(Ipv4Addr::from(octets))
+      },
+      FAMILY_IPV6 => {
+        let mut octets = [0; 16];
+        octets.get_mut(..address.len())?.copy_from_slice(address);
+        IpAddr::V6(Ipv6Addr::from(octets))
+      },
+      _ => return None,
+    };
+
+    Some(Self { address, source_prefix_len, scope_prefix_len })
+  }
+}
+
+/// Builder for an `OPT` record.
+///
+/// The UDP payload size defaults to 512. Add the built record to a query using
+/// [`Message::add_additional_record`](struct.Message.html#method.add_additional_record)
+/// to advertise EDNS support, e.g.
+/// `Opt::builder().payload_size(1232).dnssec_ok(true).build()`.
+#[derive(Debug, Clone)]
+pub struct OptBuilder {
+  udp_payload_size: u16,
+  ttl: u32,
+  options: [u8; MAX_OPTIONS_LEN],
+  options_len: usize,
+}
+
+impl Default for OptBuilder {
+  fn default() -> Self {
+    Self {
+      udp_payload_size: 512,
+      ttl: 0,
+      options: [0; MAX_OPTIONS_LEN],
+      options_len: 0,
+    }
+  }
+}
+
+impl OptBuilder {
+  #[inline]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Set the maximum UDP payload size the sender is able to receive, which is sent as the class.
+  pub fn payload_size(mut self, payload_size: u16) -> Self {
+    self.udp_payload_size = payload_size;
+    self
+  }
+
+  pub fn dnssec_ok(mut self, dnssec_ok: bool) -> Self {
+    if dnssec_ok {
+      self.ttl |= 0x8000;
+    } else {
+      self.ttl &= !0x8000;
+    }
+    self
+  }
+
+  /// Append an option with the given code and data.
+  pub fn option(mut self, code: u16, data: &[u8]) -> Result<Self, Error> {
+    let start = self.options_len + size_of::<u16>() + size_of::<u16>();
+    let end = start + data.len();
+
+    if data.len() > u16::MAX as usize || end > self.options.len() {
+      return Err(Error::BufferTooSmall)
+    }
+
+    self.options[self.options_len..(self.options_len + size_of::<u16>())].copy_from_slice(&code.to_be_bytes());
+    self.options[(start - size_of::<u16>())..start].copy_from_slice(&(data.len() as u16).to_be_bytes());
+    self.options[start..end].copy_from_slice(data);
+    self.options_len = end;
+
+    Ok(self)
+  }
+
+  /// Append a cookie option. The server cookie must be between 8 and 32 bytes long.
+  pub fn cookie(self, client: [u8; CLIENT_COOKIE_LEN], server: Option<&[u8]>) -> Result<Self, Error> {
+    let server = server.unwrap_or(&[]);
+
+    if !server.is_empty() && !(MIN_SERVER_COOKIE_LEN..=MAX_SERVER_COOKIE_LEN).contains(&server.len()) {
+      return Err(Error::InvalidOption)
+    }
+
+    let mut data = [0; CLIENT_COOKIE_LEN + MAX_SERVER_COOKIE_LEN];
+    data[..CLIENT_COOKIE_LEN].copy_from_slice(&client);
+    data[CLIENT_COOKIE_LEN..(CLIENT_COOKIE_LEN + server.len())].copy_from_slice(server);
+
+    self.option(COOKIE, &data[..(CLIENT_COOKIE_LEN + server.len())])
+  }
+
+  /// Append a client subnet option. The address is truncated to the source prefix length.
+  pub fn ecs(self, ecs: &EcsOption) -> Result<Self, Error> {
+    let mut data = [0; 4 + 16];
+    data[0..2].copy_from_slice(&ecs.family().to_be_bytes());
+    data[2] = ecs.source_prefix_len;
+    data[3] = ecs.scope_prefix_len;
+
+    let address_len = ecs.address_len();
+    match ecs.address {
+      IpAddr::V4(addr) => data[4..(4 + address_len)].copy_from_slice(&addr.octets()[..address_len]),
+      IpAddr::V6(addr) => data[4..(4 + address_len)].copy_from_slice(&addr.octets()[..address_len]),
+    }
+
+    self.option(CLIENT_SUBNET, &data[..(4 + address_len)])
+  }
+
+  /// Build an `OPT` record borrowing the options from this builder.
+  pub fn build(&self) -> Answer<'_> {
+    Answer::builder()
+      .name(Name::root())
+      .kind(QueryKind::OPT)
+      .class(QueryClass::from(self.udp_payload_size))
+      .ttl(self.ttl)
+      .rdata(&self.options[..self.options_len])
+      .build()
+  }
+}
+
+/// Iterator over the options contained in an `OPT` record, yielding the
+/// option code together with the option data.
+#[derive(Debug, Clone)]
+pub struct EdnsOptions<'a>(Tlvs<'a>);
+
+impl<'a> Iterator for EdnsOptions<'a> {
+  type Item = (u16, &'a [u8]);
+
+  #[inline]
+  fn next(&mut self) -> Option<Self::Item> {
+    self.0.next()
+  }
+}
+
+impl<'a> Answer<'a> {
+  /// Returns the data contained in an `OPT` record.
+  pub fn as_opt(&self) -> Option<Opt<'a>> {
+    if self.kind != QueryKind::OPT {
+      return None
+    }
+
+    Some(Opt {
+      udp_payload_size: u16::from(self.class),
+      ttl: self.ttl,
+      rdata: self.rdata,
+    })
+  }
+}
@@ -0,0 +1,89 @@
+use crate::{Answer, Name, QueryKind};
+
+/// The data of an `RRSIG` record.
+///
+/// According to [RFC 4034](https://tools.ietf.org/rfc/rfc4034#section-3.1).
+#[derive(Debug, Clone)]
+pub struct Rrsig<'a> {
+  type_covered: QueryKind,
+  algorithm: u8,
+  labels: u8,
+  original_ttl: u32,
+  expiration: u32,
+  inception: u32,
+  key_tag: u16,
+  signer_name: Name<'a>,
+  signature: &'a [u8],
+}
+
+impl<'a> Rrsig<'a> {
+  #[inline]
+  pub fn type_covered(&self) -> QueryKind {
+    self.type_covered
+  }
+
+  #[inline]
+  pub fn algorithm(&self) -> u8 {
+    self.algorithm
+  }
+
+  #[inline]
+  pub fn labels(&self) -> u8 {
+    self.labels
+  }
+
+  #[inline]
+  pub fn original_ttl(&self) -> u32 {
+    self.original_ttl
+  }
+
+  /// Returns the signature expiration as seconds since the Unix epoch, modulo 2<sup>32</sup>.
+  #[inline]
+  pub fn expiration(&self) -> u32 {
+    self.expiration
+  }
+
+  /// Returns the signature inception as seconds since the Unix epoch, modulo 2<sup>32</sup>.
+  #[inline]
+  pub fn inception(&self) -> u32 {
+    self.inception
+  }
+
+  #[inline]
+  pub fn key_tag(&self) -> u16 {
+    self.key_tag
+  }
+
+  #[inline]
+  pub fn signer_name(&self) -> &Name<'a> {
+    &self.signer_name
+  }
+
+  #[inline]
+  pub fn signature(&self) -> &'a [u8] {
+    self.signature
+  }
+}
+
+impl<'a> Answer<'a> {
+  /// Returns the data contained in an `RRSIG` record.
+  pub fn as_rrsig(&self) -> Option<Rrsig<'a>> {
+    if self.kind != QueryKind::RRSIG {
+      return None
+    }
+
+    let (signer_name, end) = self.read_rdata_name(18)?;
+
+    Some(Rrsig {
+      type_covered: QueryKind::from(self.rdata_u16(0)?),
+      algorithm:    *self.rdata.get(2)?,
+      labels:       *self.rdata.get(3)?,
+      original_ttl: self.rdata_u32(4)?,
+      expiration:   self.rdata_u32(8)?,
+      inception:    self.rdata_u32(12)?,
+      key_tag:      self.rdata_u16(16)?,
+      signer_name,
+      signature:    &self.rdata[end..],
+    })
+  }
+}
@@ -0,0 +1,105 @@
+use core::cmp::Ordering;
+use core::mem::size_of;
+
+use crate::{Answer, Name, QueryKind};
+
+/// A zone serial number.
+///
+/// Serial numbers wrap around, so they are compared using sequence space arithmetic according to
+/// [RFC 1982 Section 3.2](https://tools.ietf.org/rfc/rfc1982#section-3.2), e.g. `Serial(u32::MAX) < Serial(0)`.
+/// Serials which are exactly 2<sup>31</sup> apart are not comparable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Serial(pub u32);
+
+impl PartialOrd for Serial {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    const HALF: u32 = 1 << 31;
+
+    match other.0.wrapping_sub(self.0) {
+      0 => Some(Ordering::Equal),
+      HALF => None,
+      d if d < HALF => Some(Ordering::Less),
+      _ => Some(Ordering::Greater),
+    }
+  }
+}
+
+/// The data of an `SOA` record.
+///
+/// According to [RFC 1035 Section 3.3.13](https://tools.ietf.org/rfc/rfc1035#section-3.3.13).
+#[derive(Debug, Clone)]
+pub struct Soa<'a> {
+  mname: Name<'a>,
+  rname: Name<'a>,
+  serial: Serial,
+  refresh: u32,
+  retry: u32,
+  expire: u32,
+  minimum: u32,
+}
+
+impl<'a> Soa<'a> {
+  /// The name server that was the original source of data for this zone.
+  #[inline]
+  pub fn mname(&self) -> &Name<'a> {
+    &self.mname
+  }
+
+  /// The mailbox of the person responsible for this zone.
+  #[inline]
+  pub fn rname(&self) -> &Name<'a> {
+    &self.rname
+  }
+
+  #[inline]
+  pub fn serial(&self) -> Serial {
+    self.serial
+  }
+
+  #[inline]
+  pub fn refresh(&self) -> u32 {
+    self.refresh
+  }
+
+  #[inline]
+  pub fn retry(&self) -> u32 {
+    self.retry
+  }
+
+  #[inline]
+  pub fn expire(&self) -> u32 {
+    self.expire
+  }
+
+  /// The TTL used for negative caching.
+  #[inline]
+  pub fn minimum(&self) -> u32 {
+    self.minimum
+  }
+}
+
+impl<'a> Answer<'a> {
+  /// Returns the data contained in an `SOA` record.
+  pub fn as_soa(&self) -> Option<Soa<'a>> {
+    if self.kind != QueryKind::SOA {
+      return None
+    }
+
+    let (mname, i) = self.read_rdata_name(0)?;
+    let (rname, i) = self.read_rdata_name(i)?;
+
+    if i + 5 * size_of::<u32>() != self.rdata.len() {
+      return None
+    }
+
+    Some(Soa {
+      mname,
+      rname,
+      serial:  Serial(self.rdata_u32(i)?),
+      refresh: self.rdata_u32(i + 4)?,
+      retry:   self.rdata_u32(i + 8)?,
+      expire:  self.rdata_u32(i + 12)?,
+      minimum: self.rdata_u32(i + 16)?,
+    })
+  }
+}
@@ -0,0 +1,56 @@
+use crate::{Answer, Name, QueryKind};
+
+/// The data of an `SRV` record.
+///
+/// According to [RFC 2782](https://tools.ietf.org/rfc/rfc2782).
+#[derive(Debug, Clone)]
+pub struct Srv<'a> {
+  priority: u16,
+  weight: u16,
+  port: u16,
+  target: Name<'a>,
+}
+
+impl<'a> Srv<'a> {
+  #[inline]
+  pub fn priority(&self) -> u16 {
+    self.priority
+  }
+
+  #[inline]
+  pub fn weight(&self) -> u16 {
+    self.weight
+  }
+
+  #[inline]
+  pub fn port(&self) -> u16 {
+    self.port
+  }
+
+  #[inline]
+  pub fn target(&self) -> &Name<'a> {
+    &self.target
+  }
+}
+
+impl<'a> Answer<'a> {
+  /// Returns the data contained in an `SRV` record.
+  pub fn as_srv(&self) -> Option<Srv<'a>> {
+    if self.kind != QueryKind::SRV {
+      return None
+    }
+
+    let (target, end) = self.read_rdata_name(6)?;
+
+    if end != self.rdata.len() {
+      return None
+    }
+
+    Some(Srv {
+      priority: self.rdata_u16(0)?,
+      weight:   self.rdata_u16(2)?,
+      port:     self.rdata_u16(4)?,
+      target,
+    })
+  }
+}
@@ -0,0 +1,42 @@
+use crate::{Answer, QueryKind};
+
+/// The data of an `SSHFP` record.
+///
+/// According to [RFC 4255](https://tools.ietf.org/rfc/rfc4255#section-3.1).
+#[derive(Debug, Clone)]
+pub struct Sshfp<'a> {
+  algorithm: u8,
+  fingerprint_type: u8,
+  fingerprint: &'a [u8],
+}
+
+impl<'a> Sshfp<'a> {
+  #[inline]
+  pub fn algorithm(&self) -> u8 {
+    self.algorithm
+  }
+
+  #[inline]
+  pub fn fingerprint_type(&self) -> u8 {
+    self.fingerprint_type
+  }
+
+  #[inline]
+  pub fn fingerprint(&self) -> &'a [u8] {
+    self.fingerprint
+  }
+}
+
+impl<'a> Answer<'a> {
+  /// Returns the data contained in an `SSHFP` record.
+  pub fn as_sshfp(&self) -> Option<Sshfp<'a>> {
+    if self.kind != QueryKind::SSHFP {
+      return None
+    }
+
+    let (&algorithm, rest) = self.rdata.split_first()?;
+    let (&fingerprint_type, fingerprint) = rest.split_first()?;
+
+    Some(Sshfp { algorithm, fingerprint_type, fingerprint })
+  }
+}
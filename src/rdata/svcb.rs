@@ -0,0 +1,73 @@
+use core::mem::size_of;
+
+use crate::{Answer, Name, QueryKind};
+
+use super::Tlvs;
+
+/// The data of an `SVCB` or `HTTPS` record.
+///
+/// According to [RFC 9460](https://tools.ietf.org/rfc/rfc9460#section-2.2).
+#[derive(Debug, Clone)]
+pub struct Svcb<'a> {
+  priority: u16,
+  target: Name<'a>,
+  params: &'a [u8],
+}
+
+impl<'a> Svcb<'a> {
+  #[inline]
+  pub fn priority(&self) -> u16 {
+    self.priority
+  }
+
+  /// Whether this record is in AliasMode, i.e. has a priority of 0.
+  #[inline]
+  pub fn is_alias(&self) -> bool {
+    self.priority == 0
+  }
+
+  #[inline]
+  pub fn target(&self) -> &Name<'a> {
+    &self.target
+  }
+
+  /// Returns an iterator over the parameters contained in this record.
+  ///
+  /// The iterator is empty in AliasMode, since parameters must be ignored.
+  pub fn params(&self) -> SvcParams<'a> {
+    let buf = if self.is_alias() { &[] } else { self.params };
+    SvcParams(Tlvs::new(buf))
+  }
+}
+
+/// Iterator over the parameters contained in an `SVCB` or `HTTPS` record, yielding
+/// the parameter key together with the parameter value.
+#[derive(Debug, Clone)]
+pub struct SvcParams<'a>(Tlvs<'a>);
+
+impl<'a> Iterator for SvcParams<'a> {
+  type Item = (u16, &'a [u8]);
+
+  #[inline]
+  fn next(&mut self) -> Option<Self::Item> {
+    self.0.next()
+  }
+}
+
+impl<'a> Answer<'a> {
+  /// Returns the data contained in an `SVCB` or `HTTPS` record.
+  pub fn as_svcb(&self) -> Option<Svcb<'a>> {
+    if self.kind != QueryKind::SVCB && self.kind != QueryKind::HTTPS {
+      return None
+    }
+
+    let priority = self.rdata_u16(0)?;
+    let (target, end) = self.read_rdata_name(size_of::<u16>())?;
+
+    Some(Svcb {
+      priority,
+      target,
+      params: &self.rdata[end..],
+    })
+  }
+}
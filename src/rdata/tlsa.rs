@@ -0,0 +1,54 @@
+use crate::{Answer, QueryKind};
+
+/// The data of a `TLSA` record.
+///
+/// According to [RFC 6698](https://tools.ietf.org/rfc/rfc6698#section-2.1).
+#[derive(Debug, Clone)]
+pub struct Tlsa<'a> {
+  usage: u8,
+  selector: u8,
+  matching_type: u8,
+  data: &'a [u8],
+}
+
+impl<'a> Tlsa<'a> {
+  #[inline]
+  pub fn usage(&self) -> u8 {
+    self.usage
+  }
+
+  #[inline]
+  pub fn selector(&self) -> u8 {
+    self.selector
+  }
+
+  #[inline]
+  pub fn matching_type(&self) -> u8 {
+    self.matching_type
+  }
+
+  /// Returns the certificate association data.
+  #[inline]
+  pub fn data(&self) -> &'a [u8] {
+    self.data
+  }
+}
+
+impl<'a> Answer<'a> {
+  /// Returns the data contained in a `TLSA` record.
+  pub fn as_tlsa(&self) -> Option<Tlsa<'a>> {
+    if self.kind != QueryKind::TLSA {
+      return None
+    }
+
+    let (&usage, rest) = self.rdata.split_first()?;
+    let (&selector, rest) = rest.split_first()?;
+    let (&matching_type, data) = rest.split_first()?;
+
+    if data.is_empty() {
+      return None
+    }
+
+    Some(Tlsa { usage, selector, matching_type, data })
+  }
+}
@@ -0,0 +1,35 @@
+use crate::{Answer, QueryKind};
+
+/// Iterator over the character-strings contained in a `TXT` record.
+#[derive(Debug, Clone)]
+pub struct TxtStrings<'a> {
+  buf: &'a [u8],
+  buf_i: usize,
+}
+
+impl<'a> Iterator for TxtStrings<'a> {
+  type Item = &'a [u8];
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let len = *self.buf.get(self.buf_i)? as usize;
+    let start = self.buf_i + 1;
+
+    if let Some(string) = self.buf.get(start..(start + len)) {
+      self.buf_i = start + len;
+      Some(string)
+    } else {
+      self.buf_i = self.buf.len();
+      None
+    }
+  }
+}
+
+impl<'a> Answer<'a> {
+  /// Returns an iterator over the character-strings contained in a `TXT` record.
+  ///
+  /// The iterator is empty if this is not a `TXT` record.
+  pub fn txt_strings(&self) -> TxtStrings<'a> {
+    let buf = if self.kind == QueryKind::TXT { self.rdata } else { &[] };
+    TxtStrings { buf, buf_i: 0 }
+  }
+}
@@ -0,0 +1,43 @@
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::vec::Vec;
+
+#[cfg(not(feature = "std"))]
+use crate::Error;
+
+/// A byte sink messages can be written to.
+///
+/// This is [`std::io::Write`](https://doc.rust-lang.org/std/io/trait.Write.html) if the `std` feature is enabled.
+#[cfg(feature = "std")]
+pub use std::io::Write;
+
+/// A byte sink messages can be written to.
+///
+/// This is `std::io::Write` if the `std` feature is enabled.
+#[cfg(not(feature = "std"))]
+pub trait Write {
+  /// Write all of `buf`, failing with `Error::BufferTooSmall` if it does not fit.
+  fn write_all(&mut self, buf: &[u8]) -> Result<(), Error>;
+}
+
+#[cfg(not(feature = "std"))]
+impl Write for &mut [u8] {
+  fn write_all(&mut self, buf: &[u8]) -> Result<(), Error> {
+    if buf.len() > self.len() {
+      return Err(Error::BufferTooSmall)
+    }
+
+    let (head, tail) = core::mem::take(self).split_at_mut(buf.len());
+    head.copy_from_slice(buf);
+    *self = tail;
+
+    Ok(())
+  }
+}
+
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+impl Write for Vec<u8> {
+  fn write_all(&mut self, buf: &[u8]) -> Result<(), Error> {
+    self.extend_from_slice(buf);
+    Ok(())
+  }
+}
@@ -52,25 +52,25 @@ fn test_header_opcode() {
 #[test]
 fn test_header_authoritative_answer() {
   parse!(request);
-  assert_eq!(request.header().authoritative_answer(), false);
+  assert!(!request.header().authoritative_answer());
 }
 
 #[test]
 fn test_header_truncated() {
   parse!(request);
-  assert_eq!(request.header().truncated(), false);
+  assert!(!request.header().truncated());
 }
 
 #[test]
 fn test_header_recursion_desired() {
   parse!(request);
-  assert_eq!(request.header().recursion_desired(), true);
+  assert!(request.header().recursion_desired());
 }
 
 #[test]
 fn test_header_recursion_available() {
   parse!(request);
-  assert_eq!(request.header().recursion_available(), false);
+  assert!(!request.header().recursion_available());
 }
 
 #[test]
@@ -132,3 +132,2946 @@ fn test_as_bytes() {
   assert_eq!(bytes.len(), request.len());
   assert_eq!(&CAPTIVE_APPLE_COM_QUERY[..bytes.len()], bytes);
 }
+
+const CAPTIVE_APPLE_COM_AAAA_RESPONSE: [u8; 63] = [
+  30, 252,                                     // ID
+  129, 128,                                    // Kind
+  0, 1,                                        // Question Count
+  0, 1,                                        // Answer Count
+  0, 0,                                        // Name Server Count
+  0, 0,                                        // Additional Records Count
+  7, b'c', b'a', b'p', b't', b'i', b'v', b'e', // Label "captive"
+  5, b'a', b'p', b'p', b'l', b'e',             // Label "apple"
+  3, b'c', b'o', b'm',                         // Label "com"
+  0,                                           // Label End
+  0, 28,                                       // Question Kind
+  0, 1,                                        // Question Class
+  0b11000000, 12,                              // Pointer to "captive.apple.com"
+  0, 28,                                       // Answer Kind
+  0, 1,                                        // Answer Class
+  0, 0, 1, 44,                                 // TTL
+  0, 16,                                       // RDATA Length
+  0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0,          // RDATA
+  0, 0, 0, 0, 0, 0, 0, 1,
+];
+
+#[test]
+fn test_answer_as_ipv6() {
+  let mut buf = CAPTIVE_APPLE_COM_AAAA_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+  assert_eq!(*answer.kind(), QueryKind::AAAA);
+  assert_eq!(answer.as_ipv6(), Some("2001:db8::1".parse().unwrap()));
+  assert_eq!(answer.as_ipv4(), None);
+}
+
+#[test]
+fn test_answer_as_ipv6_wrong_length() {
+  let mut buf = CAPTIVE_APPLE_COM_AAAA_RESPONSE;
+  buf[46] = 15;
+  let response = Message::parse(&mut buf[..62]).expect("parsing failed");
+
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+  assert_eq!(answer.as_ipv6(), None);
+}
+
+const WWW_APPLE_COM_CNAME_RESPONSE: [u8; 53] = [
+  30, 252,                                     // ID
+  129, 128,                                    // Kind
+  0, 1,                                        // Question Count
+  0, 1,                                        // Answer Count
+  0, 0,                                        // Name Server Count
+  0, 0,                                        // Additional Records Count
+  3, b'w', b'w', b'w',                         // Label "www"
+  5, b'a', b'p', b'p', b'l', b'e',             // Label "apple"
+  3, b'c', b'o', b'm',                         // Label "com"
+  0,                                           // Label End
+  0, 1,                                        // Question Kind
+  0, 1,                                        // Question Class
+  0b11000000, 12,                              // Pointer to "www.apple.com"
+  0, 5,                                        // Answer Kind
+  0, 1,                                        // Answer Class
+  0, 0, 1, 44,                                 // TTL
+  0, 10,                                       // RDATA Length
+  7, b'c', b'a', b'p', b't', b'i', b'v', b'e', // Label "captive"
+  0b11000000, 16,                              // Pointer to "apple.com"
+];
+
+#[test]
+fn test_answer_rdata_name() {
+  let mut buf = WWW_APPLE_COM_CNAME_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+  assert_eq!(answer.name(), "www.apple.com");
+  assert_eq!(*answer.kind(), QueryKind::CNAME);
+
+  let name = answer.rdata_name().expect("CNAME target missing");
+  assert_eq!(&name, "captive.apple.com");
+  assert_eq!(answer.as_ipv4(), None);
+}
+
+const APPLE_COM_MX_RESPONSE: [u8; 63] = [
+  30, 252,                                     // ID
+  129, 128,                                    // Kind
+  0, 1,                                        // Question Count
+  0, 2,                                        // Answer Count
+  0, 0,                                        // Name Server Count
+  0, 0,                                        // Additional Records Count
+  5, b'a', b'p', b'p', b'l', b'e',             // Label "apple"
+  3, b'c', b'o', b'm',                         // Label "com"
+  0,                                           // Label End
+  0, 15,                                       // Question Kind
+  0, 1,                                        // Question Class
+  0b11000000, 12,                              // Pointer to "apple.com"
+  0, 15,                                       // Answer Kind
+  0, 1,                                        // Answer Class
+  0, 0, 14, 16,                                // TTL
+  0, 6,                                        // RDATA Length
+  0, 20,                                       // Preference
+  1, b'b',                                     // Label "b"
+  0b11000000, 12,                              // Pointer to "apple.com"
+  0b11000000, 12,                              // Pointer to "apple.com"
+  0, 15,                                       // Answer Kind
+  0, 1,                                        // Answer Class
+  0, 0, 14, 16,                                // TTL
+  0, 6,                                        // RDATA Length
+  0, 10,                                       // Preference
+  1, b'a',                                     // Label "a"
+  0b11000000, 12,                              // Pointer to "apple.com"
+];
+
+#[test]
+fn test_answer_as_mx() {
+  let mut buf = APPLE_COM_MX_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+
+  let mut exchanges = response.answers().map(|answer| answer.as_mx().expect("invalid MX record")).collect::<Vec<_>>();
+  exchanges.sort_by_key(|&(preference, _)| preference);
+
+  assert_eq!(exchanges.len(), 2);
+  assert_eq!(exchanges[0].0, 10);
+  assert_eq!(&exchanges[0].1, "a.apple.com");
+  assert_eq!(exchanges[1].0, 20);
+  assert_eq!(&exchanges[1].1, "b.apple.com");
+}
+
+const EXAMPLE_COM_TXT_RESPONSE: [u8; 54] = [
+  30, 252,                                     // ID
+  129, 128,                                    // Kind
+  0, 1,                                        // Question Count
+  0, 1,                                        // Answer Count
+  0, 0,                                        // Name Server Count
+  0, 0,                                        // Additional Records Count
+  7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', // Label "example"
+  3, b'c', b'o', b'm',                         // Label "com"
+  0,                                           // Label End
+  0, 16,                                       // Question Kind
+  0, 1,                                        // Question Class
+  0b11000000, 12,                              // Pointer to "example.com"
+  0, 16,                                       // Answer Kind
+  0, 1,                                        // Answer Class
+  0, 0, 1, 44,                                 // TTL
+  0, 13,                                       // RDATA Length
+  6, b'v', b'=', b's', b'p', b'f', b'1',       // String "v=spf1"
+  0,                                           // String ""
+  4, b'-', b'a', b'l', b'l',                   // String "-all"
+];
+
+#[test]
+fn test_answer_txt_strings() {
+  let mut buf = EXAMPLE_COM_TXT_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+
+  let mut strings = answer.txt_strings();
+  assert_eq!(strings.next(), Some(&b"v=spf1"[..]));
+  assert_eq!(strings.next(), Some(&b""[..]));
+  assert_eq!(strings.next(), Some(&b"-all"[..]));
+  assert_eq!(strings.next(), None);
+}
+
+#[test]
+fn test_answer_txt_strings_empty() {
+  let mut buf = EXAMPLE_COM_TXT_RESPONSE;
+  buf[40] = 1;
+  buf[41] = 0;
+  let response = Message::parse(&mut buf[..42]).expect("parsing failed");
+
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+
+  let mut strings = answer.txt_strings();
+  assert_eq!(strings.next(), Some(&b""[..]));
+  assert_eq!(strings.next(), None);
+}
+
+const EXAMPLE_COM_SOA_RESPONSE: [u8; 74] = [
+  30, 252,                                     // ID
+  129, 128,                                    // Kind
+  0, 1,                                        // Question Count
+  0, 1,                                        // Answer Count
+  0, 0,                                        // Name Server Count
+  0, 0,                                        // Additional Records Count
+  7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', // Label "example"
+  3, b'c', b'o', b'm',                         // Label "com"
+  0,                                           // Label End
+  0, 6,                                        // Question Kind
+  0, 1,                                        // Question Class
+  0b11000000, 12,                              // Pointer to "example.com"
+  0, 6,                                        // Answer Kind
+  0, 1,                                        // Answer Class
+  0, 0, 14, 16,                                // TTL
+  0, 33,                                       // RDATA Length
+  2, b'n', b's',                               // Label "ns"
+  0b11000000, 12,                              // Pointer to "example.com"
+  5, b'a', b'd', b'm', b'i', b'n',             // Label "admin"
+  0b11000000, 12,                              // Pointer to "example.com"
+  120, 150, 169, 225,                          // Serial
+  0, 0, 28, 32,                                // Refresh
+  0, 0, 14, 16,                                // Retry
+  0, 18, 117, 0,                               // Expire
+  0, 0, 14, 16,                                // Minimum
+];
+
+#[test]
+fn test_answer_as_soa() {
+  let mut buf = EXAMPLE_COM_SOA_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+  let soa = answer.as_soa().expect("invalid SOA record");
+
+  assert_eq!(soa.mname(), "ns.example.com");
+  assert_eq!(soa.rname(), "admin.example.com");
+  assert_eq!(soa.serial(), Serial(2023139809));
+  assert_eq!(soa.refresh(), 7200);
+  assert_eq!(soa.retry(), 3600);
+  assert_eq!(soa.expire(), 1209600);
+  assert_eq!(soa.minimum(), 3600);
+
+  assert!(answer.as_mx().is_none());
+}
+
+#[test]
+fn test_serial_ordering() {
+  assert!(Serial(1) < Serial(2));
+  assert!(Serial(u32::MAX) < Serial(0));
+  assert!(Serial(0) > Serial(u32::MAX));
+  assert!(Serial(0) < Serial((1 << 31) - 1));
+  assert!(Serial(0) > Serial((1 << 31) + 1));
+  assert_eq!(Serial(0).partial_cmp(&Serial(1 << 31)), None);
+  assert!(Serial(7) <= Serial(7));
+}
+
+#[test]
+fn test_answer_as_soa_malformed() {
+  let mut buf = EXAMPLE_COM_SOA_RESPONSE;
+  buf[40] = 32;
+  let response = Message::parse(&mut buf[..73]).expect("parsing failed");
+
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+  assert!(answer.as_soa().is_none());
+}
+
+const SIP_TCP_EXAMPLE_COM_SRV_RESPONSE: [u8; 68] = [
+  30, 252,                                     // ID
+  129, 128,                                    // Kind
+  0, 1,                                        // Question Count
+  0, 1,                                        // Answer Count
+  0, 0,                                        // Name Server Count
+  0, 0,                                        // Additional Records Count
+  4, b'_', b's', b'i', b'p',                   // Label "_sip"
+  4, b'_', b't', b'c', b'p',                   // Label "_tcp"
+  7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', // Label "example"
+  3, b'c', b'o', b'm',                         // Label "com"
+  0,                                           // Label End
+  0, 33,                                       // Question Kind
+  0, 1,                                        // Question Class
+  0b11000000, 12,                              // Pointer to "_sip._tcp.example.com"
+  0, 33,                                       // Answer Kind
+  0, 1,                                        // Answer Class
+  0, 0, 1, 44,                                 // TTL
+  0, 17,                                       // RDATA Length
+  0, 10,                                       // Priority
+  0, 60,                                       // Weight
+  19, 196,                                     // Port
+  8, b'b', b'i', b'g', b'b', b'o', b'x', b'e', b's', // Label "bigboxes"
+  0b11000000, 22,                              // Pointer to "example.com"
+];
+
+#[test]
+fn test_answer_as_srv() {
+  let mut buf = SIP_TCP_EXAMPLE_COM_SRV_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+  let srv = answer.as_srv().expect("invalid SRV record");
+
+  assert_eq!(srv.priority(), 10);
+  assert_eq!(srv.weight(), 60);
+  assert_eq!(srv.port(), 5060);
+  assert_eq!(srv.target(), "bigboxes.example.com");
+}
+
+const WWW_EXAMPLE_COM_DNAME_RESPONSE: [u8; 51] = [
+  30, 252,                                     // ID
+  129, 128,                                    // Kind
+  0, 1,                                        // Question Count
+  0, 1,                                        // Answer Count
+  0, 0,                                        // Name Server Count
+  0, 0,                                        // Additional Records Count
+  3, b'w', b'w', b'w',                         // Label "www"
+  7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', // Label "example"
+  3, b'c', b'o', b'm',                         // Label "com"
+  0,                                           // Label End
+  0, 1,                                        // Question Kind
+  0, 1,                                        // Question Class
+  0b11000000, 16,                              // Pointer to "example.com"
+  0, 39,                                       // Answer Kind
+  0, 1,                                        // Answer Class
+  0, 0, 14, 16,                                // TTL
+  0, 6,                                        // RDATA Length
+  3, b'a', b'l', b't',                         // Label "alt"
+  0b11000000, 16,                              // Pointer to "example.com"
+];
+
+#[test]
+fn test_answer_as_dname() {
+  let mut buf = WWW_EXAMPLE_COM_DNAME_RESPONSE;
+  let response = Message::parse_strict(&mut buf).expect("parsing failed");
+
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+  assert_eq!(answer.name(), "example.com");
+  assert_eq!(*answer.kind(), QueryKind::DNAME);
+  let target = answer.as_dname().expect("invalid DNAME record");
+  assert_eq!(&target, "alt.example.com");
+  assert!(matches!(answer.parse_rdata(), Rdata::Dname(name) if name == target));
+  assert!(answer.rdata_name().is_none());
+
+  let mut out = [0; 64];
+  let len = answer.write(&mut out).expect("writing answer failed");
+  assert_eq!(&out[(len - 17)..len], b"\x03alt\x07example\x03com\x00");
+}
+
+const EXAMPLE_COM_CAA_RESPONSE: [u8; 63] = [
+  30, 252,                                     // ID
+  129, 128,                                    // Kind
+  0, 1,                                        // Question Count
+  0, 1,                                        // Answer Count
+  0, 0,                                        // Name Server Count
+  0, 0,                                        // Additional Records Count
+  7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', // Label "example"
+  3, b'c', b'o', b'm',                         // Label "com"
+  0,                                           // Label End
+  1, 1,                                        // Question Kind
+  0, 1,                                        // Question Class
+  0b11000000, 12,                              // Pointer to "example.com"
+  1, 1,                                        // Answer Kind
+  0, 1,                                        // Answer Class
+  0, 0, 1, 44,                                 // TTL
+  0, 22,                                       // RDATA Length
+  128,                                         // Flags
+  5, b'i', b's', b's', b'u', b'e',             // Tag "issue"
+  b'l', b'e', b't', b's', b'e', b'n', b'c', b'r', b'y', b'p', b't', b'.', b'o', b'r', b'g', // Value
+];
+
+#[test]
+fn test_answer_as_caa() {
+  let mut buf = EXAMPLE_COM_CAA_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+  assert_eq!(*answer.kind(), QueryKind::CAA);
+
+  let caa = answer.as_caa().expect("invalid CAA record");
+  assert_eq!(caa.flags(), 128);
+  assert!(caa.critical());
+  assert_eq!(caa.tag(), "issue");
+  assert_eq!(caa.value(), b"letsencrypt.org");
+}
+
+#[test]
+fn test_answer_as_caa_tag_too_long() {
+  let mut buf = EXAMPLE_COM_CAA_RESPONSE;
+  buf[42] = 23;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+  assert!(answer.as_caa().is_none());
+}
+
+const EXAMPLE_COM_NAPTR_RESPONSE: [u8; 68] = [
+  30, 252,                                     // ID
+  129, 128,                                    // Kind
+  0, 1,                                        // Question Count
+  0, 1,                                        // Answer Count
+  0, 0,                                        // Name Server Count
+  0, 0,                                        // Additional Records Count
+  7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', // Label "example"
+  3, b'c', b'o', b'm',                         // Label "com"
+  0,                                           // Label End
+  0, 35,                                       // Question Kind
+  0, 1,                                        // Question Class
+  0b11000000, 12,                              // Pointer to "example.com"
+  0, 35,                                       // Answer Kind
+  0, 1,                                        // Answer Class
+  0, 0, 1, 44,                                 // TTL
+  0, 27,                                       // RDATA Length
+  0, 100,                                      // Order
+  0, 10,                                       // Preference
+  1, b'S',                                     // Flags "S"
+  7, b'S', b'I', b'P', b'+', b'D', b'2', b'U', // Services "SIP+D2U"
+  0,                                           // Regexp ""
+  4, b'_', b's', b'i', b'p',                   // Label "_sip"
+  4, b'_', b'u', b'd', b'p',                   // Label "_udp"
+  0b11000000, 12,                              // Pointer to "example.com"
+];
+
+#[test]
+fn test_answer_as_naptr() {
+  let mut buf = EXAMPLE_COM_NAPTR_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+  assert_eq!(*answer.kind(), QueryKind::NAPTR);
+
+  let naptr = answer.as_naptr().expect("invalid NAPTR record");
+  assert_eq!(naptr.order(), 100);
+  assert_eq!(naptr.preference(), 10);
+  assert_eq!(naptr.flags(), b"S");
+  assert_eq!(naptr.services(), b"SIP+D2U");
+  assert_eq!(naptr.regexp(), b"");
+  assert_eq!(naptr.replacement(), "_sip._udp.example.com");
+
+  assert!(matches!(answer.parse_rdata(), Rdata::Naptr(_)));
+}
+
+#[test]
+fn test_answer_as_naptr_string_too_long() {
+  let mut buf = EXAMPLE_COM_NAPTR_RESPONSE;
+  // Services length exceeding the RDATA.
+  buf[47] = 40;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+  assert!(answer.as_naptr().is_none());
+}
+
+const EXAMPLE_COM_HTTPS_RESPONSE: [u8; 54] = [
+  30, 252,                                     // ID
+  129, 128,                                    // Kind
+  0, 1,                                        // Question Count
+  0, 1,                                        // Answer Count
+  0, 0,                                        // Name Server Count
+  0, 0,                                        // Additional Records Count
+  7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', // Label "example"
+  3, b'c', b'o', b'm',                         // Label "com"
+  0,                                           // Label End
+  0, 65,                                       // Question Kind
+  0, 1,                                        // Question Class
+  0b11000000, 12,                              // Pointer to "example.com"
+  0, 65,                                       // Answer Kind
+  0, 1,                                        // Answer Class
+  0, 0, 1, 44,                                 // TTL
+  0, 13,                                       // RDATA Length
+  0, 1,                                        // Priority
+  0,                                           // Target "."
+  0, 1,                                        // Key "alpn"
+  0, 6,                                        // Value Length
+  2, b'h', b'2', 2, b'h', b'3',                // Value "h2,h3"
+];
+
+#[test]
+fn test_answer_as_svcb() {
+  let mut buf = EXAMPLE_COM_HTTPS_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+  assert_eq!(*answer.kind(), QueryKind::HTTPS);
+
+  let https = answer.as_svcb().expect("invalid HTTPS record");
+  assert_eq!(https.priority(), 1);
+  assert!(!https.is_alias());
+  assert!(https.target().is_root());
+
+  let mut params = https.params();
+  assert_eq!(params.next(), Some((1, &[2, b'h', b'2', 2, b'h', b'3'][..])));
+  assert_eq!(params.next(), None);
+
+  assert!(matches!(answer.parse_rdata(), Rdata::Https(_)));
+}
+
+#[test]
+fn test_answer_as_svcb_alias() {
+  let mut buf = EXAMPLE_COM_HTTPS_RESPONSE;
+  // Priority 0
+  buf[42] = 0;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+  let https = answer.as_svcb().expect("invalid HTTPS record");
+  assert!(https.is_alias());
+  assert_eq!(https.params().count(), 0);
+}
+
+const HOST_EXAMPLE_COM_SSHFP_RESPONSE: [u8; 80] = [
+  30, 252,                                     // ID
+  129, 128,                                    // Kind
+  0, 1,                                        // Question Count
+  0, 1,                                        // Answer Count
+  0, 0,                                        // Name Server Count
+  0, 0,                                        // Additional Records Count
+  4, b'h', b'o', b's', b't',                   // Label "host"
+  7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', // Label "example"
+  3, b'c', b'o', b'm',                         // Label "com"
+  0,                                           // Label End
+  0, 44,                                       // Question Kind
+  0, 1,                                        // Question Class
+  0b11000000, 12,                              // Pointer to "host.example.com"
+  0, 44,                                       // Answer Kind
+  0, 1,                                        // Answer Class
+  0, 0, 1, 44,                                 // TTL
+  0, 34,                                       // RDATA Length
+  4,                                           // Algorithm "Ed25519"
+  2,                                           // Fingerprint Type "SHA-256"
+  0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0, // Fingerprint
+  0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0,
+  0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0,
+  0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0,
+];
+
+#[test]
+fn test_answer_as_sshfp() {
+  let mut buf = HOST_EXAMPLE_COM_SSHFP_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+  assert_eq!(*answer.kind(), QueryKind::SSHFP);
+
+  let sshfp = answer.as_sshfp().expect("invalid SSHFP record");
+  assert_eq!(sshfp.algorithm(), 4);
+  assert_eq!(sshfp.fingerprint_type(), 2);
+  assert_eq!(sshfp.fingerprint().len(), 32);
+  assert_eq!(&sshfp.fingerprint()[..4], &[0x12, 0x34, 0x56, 0x78]);
+
+  assert!(matches!(answer.parse_rdata(), Rdata::Sshfp(_)));
+
+  let answer = Answer::builder().kind(QueryKind::SSHFP).rdata(&[4]).build();
+  assert!(answer.as_sshfp().is_none());
+}
+
+const PORT_443_TCP_EXAMPLE_COM_TLSA_RESPONSE: [u8; 86] = [
+  30, 252,                                     // ID
+  129, 128,                                    // Kind
+  0, 1,                                        // Question Count
+  0, 1,                                        // Answer Count
+  0, 0,                                        // Name Server Count
+  0, 0,                                        // Additional Records Count
+  4, b'_', b'4', b'4', b'3',                   // Label "_443"
+  4, b'_', b't', b'c', b'p',                   // Label "_tcp"
+  7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', // Label "example"
+  3, b'c', b'o', b'm',                         // Label "com"
+  0,                                           // Label End
+  0, 52,                                       // Question Kind
+  0, 1,                                        // Question Class
+  0b11000000, 12,                              // Pointer to "_443._tcp.example.com"
+  0, 52,                                       // Answer Kind
+  0, 1,                                        // Answer Class
+  0, 0, 1, 44,                                 // TTL
+  0, 35,                                       // RDATA Length
+  3,                                           // Usage "DANE-EE"
+  1,                                           // Selector "SPKI"
+  1,                                           // Matching Type "SHA-256"
+  0xd2, 0xab, 0xde, 0x24, 0x0d, 0x7c, 0xd3, 0xee, // Certificate Association Data
+  0x6b, 0x4b, 0x28, 0xc5, 0x4d, 0xf0, 0x34, 0xb9,
+  0x79, 0x83, 0xa1, 0xd1, 0x6e, 0x8a, 0x41, 0x0e,
+  0x45, 0x61, 0xcb, 0x10, 0x66, 0x18, 0xe9, 0x71,
+];
+
+#[test]
+fn test_answer_as_tlsa() {
+  let mut buf = PORT_443_TCP_EXAMPLE_COM_TLSA_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+  assert_eq!(*answer.kind(), QueryKind::TLSA);
+  assert_eq!(answer.name(), "_443._tcp.example.com");
+
+  let tlsa = answer.as_tlsa().expect("invalid TLSA record");
+  assert_eq!(tlsa.usage(), 3);
+  assert_eq!(tlsa.selector(), 1);
+  assert_eq!(tlsa.matching_type(), 1);
+  assert_eq!(tlsa.data().len(), 32);
+
+  assert!(matches!(answer.parse_rdata(), Rdata::Tlsa(_)));
+
+  // Certificate association data must not be empty.
+  let answer = Answer::builder().kind(QueryKind::TLSA).rdata(&[3, 1, 1]).build();
+  assert!(answer.as_tlsa().is_none());
+}
+
+const EXAMPLE_COM_DS_RESPONSE: [u8; 77] = [
+  30, 252,                                     // ID
+  129, 128,                                    // Kind
+  0, 1,                                        // Question Count
+  0, 1,                                        // Answer Count
+  0, 0,                                        // Name Server Count
+  0, 0,                                        // Additional Records Count
+  7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', // Label "example"
+  3, b'c', b'o', b'm',                         // Label "com"
+  0,                                           // Label End
+  0, 43,                                       // Question Kind
+  0, 1,                                        // Question Class
+  0b11000000, 12,                              // Pointer to "example.com"
+  0, 43,                                       // Answer Kind
+  0, 1,                                        // Answer Class
+  0, 0, 1, 44,                                 // TTL
+  0, 36,                                       // RDATA Length
+  0x4f, 0x66,                                  // Key Tag
+  13,                                          // Algorithm "ECDSAP256SHA256"
+  2,                                           // Digest Type "SHA-256"
+  0x8a, 0xa1, 0x84, 0x33, 0x0b, 0x8f, 0x33, 0x9b, // Digest
+  0x9d, 0x2e, 0x4e, 0x88, 0x24, 0x3c, 0xee, 0x4d,
+  0x2b, 0x7b, 0xb9, 0x58, 0x01, 0x18, 0xfc, 0x06,
+  0x2a, 0x6f, 0x1b, 0x84, 0x2c, 0x5a, 0x97, 0x1d,
+];
+
+#[test]
+fn test_answer_as_ds() {
+  let mut buf = EXAMPLE_COM_DS_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+  assert_eq!(*answer.kind(), QueryKind::DS);
+
+  let ds = answer.as_ds().expect("invalid DS record");
+  assert_eq!(ds.key_tag(), 20326);
+  assert_eq!(ds.algorithm(), 13);
+  assert_eq!(ds.digest_type(), 2);
+  assert_eq!(ds.digest().len(), 32);
+
+  assert!(matches!(answer.parse_rdata(), Rdata::Ds(_)));
+
+  // The digest must not be empty.
+  let answer = Answer::builder().kind(QueryKind::DS).rdata(&[0x4f, 0x66, 13, 2]).build();
+  assert!(answer.as_ds().is_none());
+}
+
+const EXAMPLE_COM_RRSIG_RESPONSE: [u8; 77] = [
+  30, 252,                                     // ID
+  129, 128,                                    // Kind
+  0, 1,                                        // Question Count
+  0, 1,                                        // Answer Count
+  0, 0,                                        // Name Server Count
+  0, 0,                                        // Additional Records Count
+  7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', // Label "example"
+  3, b'c', b'o', b'm',                         // Label "com"
+  0,                                           // Label End
+  0, 46,                                       // Question Kind
+  0, 1,                                        // Question Class
+  0b11000000, 12,                              // Pointer to "example.com"
+  0, 46,                                       // Answer Kind
+  0, 1,                                        // Answer Class
+  0, 0, 1, 44,                                 // TTL
+  0, 36,                                       // RDATA Length
+  0, 1,                                        // Type Covered
+  13,                                          // Algorithm "ECDSAP256SHA256"
+  2,                                           // Labels
+  0, 0, 1, 44,                                 // Original TTL
+  0x65, 0xb5, 0x3e, 0x80,                      // Signature Expiration
+  0x65, 0x92, 0xa4, 0x80,                      // Signature Inception
+  0x4f, 0x66,                                  // Key Tag
+  0b11000000, 12,                              // Pointer to "example.com"
+  0x1b, 0x2c, 0x3d, 0x4e, 0x5f, 0x60, 0x71, 0x82, // Signature
+  0x93, 0xa4, 0xb5, 0xc6, 0xd7, 0xe8, 0xf9, 0x0a,
+];
+
+#[test]
+fn test_answer_as_rrsig() {
+  let mut buf = EXAMPLE_COM_RRSIG_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+  assert_eq!(*answer.kind(), QueryKind::RRSIG);
+
+  let rrsig = answer.as_rrsig().expect("invalid RRSIG record");
+  assert_eq!(rrsig.type_covered(), QueryKind::A);
+  assert_eq!(rrsig.algorithm(), 13);
+  assert_eq!(rrsig.labels(), 2);
+  assert_eq!(rrsig.original_ttl(), 300);
+  assert_eq!(rrsig.expiration(), 1706376832);
+  assert_eq!(rrsig.inception(), 1704109184);
+  assert_eq!(rrsig.key_tag(), 20326);
+  assert_eq!(rrsig.signer_name(), "example.com");
+  assert_eq!(rrsig.signature().len(), 16);
+
+  assert!(matches!(answer.parse_rdata(), Rdata::Rrsig(_)));
+
+  let answer = Answer::builder().kind(QueryKind::RRSIG).rdata(&buf[41..55]).build();
+  assert!(answer.as_rrsig().is_none());
+}
+
+#[test]
+fn test_answer_as_hinfo() {
+  let rdata = [
+    5, b'I', b'N', b'T', b'E', b'L', // CPU "INTEL"
+    7, b'W', b'I', b'N', b'D', b'O', b'W', b'S', // OS "WINDOWS"
+  ];
+  let answer = Answer::builder().kind(QueryKind::HINFO).rdata(&rdata).build();
+  assert_eq!(answer.as_hinfo(), Some(("INTEL", "WINDOWS")));
+  assert!(matches!(answer.parse_rdata(), Rdata::Hinfo { cpu: "INTEL", os: "WINDOWS" }));
+
+  // OS exceeding the RDATA.
+  let answer = Answer::builder().kind(QueryKind::HINFO).rdata(&rdata[..10]).build();
+  assert_eq!(answer.as_hinfo(), None);
+
+  // RFC 8482 response to an `ANY` query.
+  let rdata = [7, b'R', b'F', b'C', b'8', b'4', b'8', b'2', 0];
+  let answer = Answer::builder().kind(QueryKind::HINFO).rdata(&rdata).build();
+  assert_eq!(answer.as_hinfo(), Some(("RFC8482", "")));
+}
+
+#[test]
+fn test_answer_as_loc() {
+  // 42 21 54 N 71 06 18 W -24m 30m
+  let rdata = [
+    0,                  // Version
+    0x33,               // Size
+    0x16,               // Horizontal Precision
+    0x13,               // Vertical Precision
+    137, 23, 45, 208,   // Latitude
+    112, 190, 21, 240,  // Longitude
+    0, 152, 141, 32,    // Altitude
+  ];
+  let answer = Answer::builder().kind(QueryKind::LOC).rdata(&rdata).build();
+  let loc = answer.as_loc().expect("invalid LOC record");
+
+  assert_eq!(loc.version(), 0);
+  assert_eq!(loc.size(), 0x33);
+  assert_eq!(loc.size_cm(), 3000);
+  assert_eq!(loc.horizontal_precision_cm(), 1_000_000);
+  assert_eq!(loc.vertical_precision_cm(), 1000);
+  assert_eq!(loc.latitude(), 2299997648);
+  assert_eq!(loc.latitude_mas(), ((42 * 60 + 21) * 60 + 54) * 1000);
+  assert_eq!(loc.longitude_mas(), -((71 * 60 + 6) * 60 + 18) * 1000);
+  assert_eq!(loc.altitude_cm(), -2400);
+  assert!(matches!(answer.parse_rdata(), Rdata::Loc(l) if l == loc));
+
+  let mut rdata = rdata;
+  rdata[0] = 1;
+  let answer = Answer::builder().kind(QueryKind::LOC).rdata(&rdata).build();
+  assert!(answer.as_loc().is_none());
+
+  let answer = Answer::builder().kind(QueryKind::LOC).rdata(&rdata[..15]).build();
+  assert!(answer.as_loc().is_none());
+}
+
+#[test]
+fn test_question_answer_index() {
+  let mut buf = WWW_APPLE_COM_CNAME_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+
+  let question = response.question(0).expect("question missing");
+  assert_eq!(question.name(), "www.apple.com");
+  assert!(response.question(1).is_none());
+
+  let answer = response.answer(0).expect("answer missing");
+  assert_eq!(*answer.kind(), QueryKind::CNAME);
+  assert!(response.answer(1).is_none());
+
+  parse!(request);
+  assert!(request.answer(0).is_none());
+}
+
+#[test]
+fn test_iterators_clone() {
+  let mut buf = Message::BUFFER;
+  buf[..53].copy_from_slice(&WWW_APPLE_COM_CNAME_RESPONSE);
+  let mut response = Message::parse(&mut buf).expect("parsing failed");
+
+  let mut name_buf = [0; 32];
+  let name = Name::parse_str("captive.apple.com", &mut name_buf).expect("parsing name failed");
+  response.add_answer(&Answer::builder().name(name).rdata(&[192, 0, 2, 1]).build()).expect("adding answer failed");
+
+  let mut answers = response.answers();
+  let first = answers.next().expect("`Answers` iterator is empty");
+  assert_eq!(*first.kind(), QueryKind::CNAME);
+
+  let mut rest = answers.clone();
+  assert_eq!(rest.len(), 1);
+  assert_eq!(rest.next().map(|answer| *answer.kind()), Some(QueryKind::A));
+  assert!(rest.next().is_none());
+
+  assert_eq!(answers.len(), 1);
+  assert_eq!(answers.next().map(|answer| *answer.kind()), Some(QueryKind::A));
+  assert!(answers.next().is_none());
+
+  let mut questions = response.questions();
+  let copy = questions.clone();
+  assert!(questions.next().is_some());
+  assert!(questions.next().is_none());
+  assert_eq!(copy.count(), 1);
+}
+
+#[test]
+fn test_answers_of_kind() {
+  let mut buf = Message::BUFFER;
+  buf[..53].copy_from_slice(&WWW_APPLE_COM_CNAME_RESPONSE);
+  let mut response = Message::parse(&mut buf).expect("parsing failed");
+
+  let mut name_buf = [0; 32];
+  let name = Name::parse_str("captive.apple.com", &mut name_buf).expect("parsing name failed");
+  response.add_answer(&Answer::builder().name(name).rdata(&[192, 0, 2, 1]).build()).expect("adding answer failed");
+  assert_eq!(response.answers().count(), 2);
+
+  let mut answers = response.answers_of_kind(QueryKind::A);
+  let answer = answers.next().expect("iterator is empty");
+  assert_eq!(answer.name(), "captive.apple.com");
+  assert_eq!(answer.as_ipv4(), Some([192, 0, 2, 1].into()));
+  assert!(answers.next().is_none());
+
+  assert_eq!(response.answers_of_kind(QueryKind::CNAME).count(), 1);
+  assert_eq!(response.answers_of_kind(QueryKind::AAAA).count(), 0);
+}
+
+#[test]
+fn test_find_answer() {
+  let mut buf = Message::BUFFER;
+  buf[..53].copy_from_slice(&WWW_APPLE_COM_CNAME_RESPONSE);
+  let mut response = Message::parse(&mut buf).expect("parsing failed");
+
+  let mut name_buf = [0; 32];
+  let name = Name::parse_str("captive.apple.com", &mut name_buf).expect("parsing name failed");
+  response.add_answer(&Answer::builder().name(name).rdata(&[192, 0, 2, 1]).build()).expect("adding answer failed");
+
+  // Follow the CNAME chain.
+  let mut name_buf = [0; 32];
+  let name = Name::parse_str("WWW.apple.com", &mut name_buf).expect("parsing name failed");
+  let cname = response.find_answer(&name).expect("answer not found");
+  assert_eq!(*cname.kind(), QueryKind::CNAME);
+
+  let target = cname.rdata_name().expect("invalid CNAME record");
+  let answer = response.find_answer(&target).expect("answer not found");
+  assert_eq!(*answer.kind(), QueryKind::A);
+  assert_eq!(answer.as_ipv4(), Some([192, 0, 2, 1].into()));
+
+  let mut name_buf = [0; 32];
+  let name = Name::parse_str("apple.com", &mut name_buf).expect("parsing name failed");
+  assert!(response.find_answer(&name).is_none());
+}
+
+#[test]
+fn test_resolve_cname() {
+  let mut buf = Message::BUFFER;
+  buf[..53].copy_from_slice(&WWW_APPLE_COM_CNAME_RESPONSE);
+  let mut response = Message::parse(&mut buf).expect("parsing failed");
+
+  let mut name_buf = [0; 32];
+  let captive = Name::parse_str("captive.apple.com", &mut name_buf).expect("parsing name failed");
+  let mut name_buf = [0; 32];
+  let edge = Name::parse_str("edge.apple.com", &mut name_buf).expect("parsing name failed");
+  let mut rdata = [0; 32];
+  let len = edge.write_canonical(&mut rdata).expect("writing name failed");
+  response.add_answer(&Answer::builder().name(captive.clone()).kind(QueryKind::CNAME).rdata(&rdata[..len]).build()).expect("adding answer failed");
+  response.add_answer(&Answer::builder().name(edge.clone()).rdata(&[192, 0, 2, 1]).build()).expect("adding answer failed");
+
+  let mut name_buf = [0; 32];
+  let www = Name::parse_str("www.apple.com", &mut name_buf).expect("parsing name failed");
+  assert_eq!(response.resolve_cname(&www), Some(edge.clone()));
+  assert_eq!(response.resolve_cname(&captive), Some(edge.clone()));
+  assert_eq!(response.resolve_cname(&edge), None);
+
+  // Point `edge.apple.com` back at the start of the chain.
+  assert!(response.remove_last_answer());
+  let mut rdata = [0; 32];
+  let len = www.write_canonical(&mut rdata).expect("writing name failed");
+  response.add_answer(&Answer::builder().name(edge.clone()).kind(QueryKind::CNAME).rdata(&rdata[..len]).build()).expect("adding answer failed");
+  assert_eq!(response.resolve_cname(&www), None);
+}
+
+#[test]
+fn test_question_unicast_response() {
+  let mut buf = CAPTIVE_APPLE_COM_QUERY;
+  buf[33] |= 0x80;
+
+  let query = Message::parse(&mut buf).expect("parsing failed");
+  let mut question = query.questions().next().expect("`Questions` iterator is empty");
+  assert_eq!(*question.class(), QueryClass::IN);
+  assert!(question.unicast_response());
+
+  let mut out = Message::BUFFER;
+  let mut message = Message::builder(&mut out).build();
+  message.add_question(&question).expect("adding question failed");
+  assert_eq!(&message.as_bytes()[12..], &query.as_bytes()[12..]);
+
+  question.set_unicast_response(false);
+  assert!(!question.unicast_response());
+
+  let mut message = Message::builder(&mut out).build();
+  message.add_question(&question).expect("adding question failed");
+  assert_eq!(&message.as_bytes()[12..], &CAPTIVE_APPLE_COM_QUERY[12..35]);
+}
+
+#[test]
+fn test_answer_cache_flush() {
+  let mut buf = CAPTIVE_APPLE_COM_AAAA_RESPONSE;
+  buf[39] |= 0x80;
+
+  let response = Message::parse(&mut buf).expect("parsing failed");
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+  assert_eq!(*answer.class(), QueryClass::IN);
+  assert!(answer.cache_flush());
+
+  let mut out = Message::BUFFER;
+  let mut message = Message::builder(&mut out).build();
+  message.add_answer(&answer).expect("adding answer failed");
+  let answer = message.answers().next().expect("`Answers` iterator is empty");
+  assert!(answer.cache_flush());
+
+  let answer = Answer::builder().cache_flush(true).build();
+  assert!(answer.cache_flush());
+  assert!(!Answer::builder().build().cache_flush());
+
+  // The class of an `OPT` record is the UDP payload size.
+  let mut buf = EXAMPLE_COM_NSID_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+  let opt = response.additional_records().next().expect("`Answers` iterator is empty");
+  assert!(!opt.cache_flush());
+  assert_eq!(opt.as_opt().expect("invalid OPT record").udp_payload_size(), 4096);
+}
+
+#[test]
+fn test_is_query() {
+  parse!(query);
+  assert!(query.is_query());
+  assert!(!query.is_response());
+
+  let mut buf = CAPTIVE_APPLE_COM_AAAA_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+  assert!(!response.is_query());
+  assert!(response.is_response());
+}
+
+#[test]
+fn test_message_query() {
+  let mut buf = Message::BUFFER;
+  let query = Message::query("captive.apple.com", QueryKind::A, &mut buf).expect("creating query failed");
+
+  let mut expected = CAPTIVE_APPLE_COM_QUERY;
+  expected[..2].copy_from_slice(&[0, 0]);
+  assert_eq!(query.as_bytes(), &expected[..35]);
+  assert!(query.header().recursion_desired());
+
+  let mut buf = Message::BUFFER;
+  assert_eq!(Message::query("captive..com", QueryKind::A, &mut buf).err(), Some(Error::EmptyLabel));
+}
+
+#[test]
+fn test_dedup_answers() {
+  parse!(query);
+  let question = query.questions().next().expect("`Questions` iterator is empty");
+
+  let mut buf = Message::BUFFER;
+  let mut response = Message::builder(&mut buf).build();
+  response.add_question(&question).expect("adding question failed");
+
+  let mut name_buf = [0; 32];
+  let name = Name::parse_str("CAPTIVE.apple.com", &mut name_buf).expect("parsing name failed");
+  let answer = |ttl| Answer::builder().name(name.clone()).ttl(ttl).rdata(&[192, 0, 2, 1]).build();
+
+  response.add_answer(&answer(300)).expect("adding answer failed");
+  response.add_answer(&Answer::builder().name(question.name().clone()).rdata(&[192, 0, 2, 2]).build()).expect("adding answer failed");
+  response.add_answer(&answer(60)).expect("adding answer failed");
+  response.add_answer(&answer(120)).expect("adding answer failed");
+  assert_eq!(response.header().answer_count(), 4);
+
+  response.dedup_answers();
+  assert_eq!(response.header().answer_count(), 2);
+
+  let mut answers = response.answers();
+  let answer = answers.next().expect("`Answers` iterator is empty");
+  assert_eq!(answer.as_ipv4(), Some([192, 0, 2, 1].into()));
+  assert_eq!(answer.ttl(), 60);
+  let answer = answers.next().expect("`Answers` iterator is empty");
+  assert_eq!(answer.as_ipv4(), Some([192, 0, 2, 2].into()));
+  assert!(answers.next().is_none());
+
+  let mut bytes = response.to_vec();
+  assert!(Message::parse_strict(&mut bytes).is_ok());
+}
+
+#[test]
+fn test_append_answers_from() {
+  let mut name_buf = [0; 32];
+  let name = Name::parse_str("example.com", &mut name_buf).expect("parsing name failed");
+  let mut alias_buf = [0; 32];
+  let alias = Name::parse_str("www.example.com", &mut alias_buf).expect("parsing name failed");
+
+  let mut buf = Message::BUFFER;
+  let mut message = Message::builder(&mut buf)
+    .answer(&Answer::builder().name(name.clone()).ttl(300).rdata(&[192, 0, 2, 1]).build()).expect("adding answer failed")
+    .build();
+
+  // The CNAME target `captive.apple.com` is compressed using a pointer into `other`.
+  let mut other_buf = WWW_APPLE_COM_CNAME_RESPONSE;
+  let other = Message::parse(&mut other_buf).expect("parsing failed");
+
+  message.append_answers_from(&other).expect("appending answers failed");
+  assert_eq!(message.header().answer_count(), 2);
+
+  let mut answers = message.answers();
+  let answer = answers.next().expect("`Answers` iterator is empty");
+  assert_eq!(answer.name(), &name);
+  assert_eq!(answer.as_ipv4(), Some([192, 0, 2, 1].into()));
+  let answer = answers.next().expect("`Answers` iterator is empty");
+  assert_eq!(answer.name(), "www.apple.com");
+  assert_eq!(answer.ttl(), 300);
+  assert_eq!(&answer.rdata_name().expect("invalid CNAME record"), "captive.apple.com");
+  assert!(answers.next().is_none());
+
+  let mut bytes = message.to_vec();
+  assert!(Message::parse_strict(&mut bytes).is_ok());
+
+  let mut small_buf = [0; 40];
+  let mut small = Message::builder(&mut small_buf).build();
+  assert_eq!(small.append_answers_from(&message), Err(Error::MessageTooLong));
+
+  let mut soa_buf = EXAMPLE_COM_SOA_RESPONSE;
+  let soa_response = Message::parse(&mut soa_buf).expect("parsing failed");
+
+  let mut buf = Message::BUFFER;
+  let mut message = Message::builder(&mut buf)
+    .question(&Question::new(alias.clone(), QueryKind::SOA, QueryClass::IN)).expect("adding question failed")
+    .build();
+  message.append_answers_from(&soa_response).expect("appending answers failed");
+
+  let mut bytes = message.to_vec();
+  let message = Message::parse(&mut bytes).expect("parsing failed");
+  let soa = message.answers().next().and_then(|answer| answer.as_soa()).expect("invalid SOA record");
+  assert_eq!(soa.mname(), "ns.example.com");
+  assert_eq!(soa.rname(), "admin.example.com");
+  assert_eq!(soa.minimum(), 3600);
+
+  // Point the first MX exchange name past the end of `other`.
+  let mut other_buf = APPLE_COM_MX_RESPONSE;
+  other_buf[44] = 0xFF;
+  let other = Message::parse(&mut other_buf).expect("parsing failed");
+
+  let mut buf = Message::BUFFER;
+  let mut message = Message::builder(&mut buf).build();
+  assert!(matches!(message.append_answers_from(&other), Err(Error::InvalidRdata { .. })));
+  assert_eq!(message.header().answer_count(), 0);
+}
+
+#[test]
+fn test_append_answers_from_max_len() {
+  let mut name_buf = [0; 32];
+  let name = Name::parse_str("example.com", &mut name_buf).expect("parsing name failed");
+  let rdata = [0; 200];
+
+  let mut other_buf = Message::BUFFER;
+  let other = Message::builder(&mut other_buf)
+    .answer(&Answer::builder().name(name).kind(QueryKind::TXT).rdata(&rdata).build()).expect("adding answer failed")
+    .build();
+
+  // The buffer is larger than 512 bytes, but without `OPT` record the message is limited to 512 bytes.
+  let mut buf = [0; 1024];
+  let mut message = Message::builder(&mut buf).build();
+  message.append_answers_from(&other).expect("appending answers failed");
+  message.append_answers_from(&other).expect("appending answers failed");
+  assert_eq!(message.append_answers_from(&other), Err(Error::MessageTooLong));
+  assert_eq!(message.header().answer_count(), 2);
+  assert!(message.as_bytes().len() <= 512);
+
+  let mut bytes = message.to_vec();
+  assert!(Message::parse(&mut bytes).is_ok());
+
+  let mut buf = [0; 1024];
+  let mut message = Message::builder(&mut buf)
+    .additional_record(&Opt::builder().payload_size(1024).build()).expect("adding OPT record failed")
+    .build();
+  message.append_answers_from(&other).expect("appending answers failed");
+  message.append_answers_from(&other).expect("appending answers failed");
+  message.append_answers_from(&other).expect("appending answers failed");
+  assert_eq!(message.header().answer_count(), 3);
+  assert!(message.as_bytes().len() > 512);
+}
+
+#[test]
+fn test_answer_builder() {
+  parse!(query);
+  let question = query.questions().next().expect("`Questions` iterator is empty");
+
+  let mut buf = Message::BUFFER;
+  let mut response = Message::builder(&mut buf)
+    .header(Header::builder().id(query.header().id()).kind(HeaderKind::Response).build())
+    .build();
+
+  let answer = Answer::builder()
+    .name(question.name().clone())
+    .kind(QueryKind::A)
+    .ttl(300)
+    .rdata(&[192, 0, 2, 1])
+    .build();
+
+  response.add_question(&question).expect("adding question failed");
+  response.add_answer(&answer).expect("adding answer failed");
+
+  let mut bytes = response.to_vec();
+  let response = Message::parse(&mut bytes).expect("parsing failed");
+
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+  assert_eq!(answer.name(), "captive.apple.com");
+  assert_eq!(*answer.class(), QueryClass::IN);
+  assert_eq!(answer.ttl(), 300);
+  assert_eq!(answer.as_ipv4(), Some([192, 0, 2, 1].into()));
+}
+
+#[test]
+fn test_answer_builder_defaults() {
+  let answer = Answer::builder().build();
+
+  assert_eq!(answer.name().to_string(), "");
+  assert_eq!(*answer.class(), QueryClass::IN);
+  assert_eq!(answer.ttl(), 0);
+  assert!(answer.rdata().is_empty());
+}
+
+#[test]
+fn test_answer_ttl_duration() {
+  use std::time::Duration;
+
+  let answer = Answer::builder().ttl(3600).build();
+  assert_eq!(answer.ttl_duration(), Duration::from_secs(60 * 60));
+
+  let answer = Answer::builder().ttl_duration(Duration::from_millis(1500)).build();
+  assert_eq!(answer.ttl(), 1);
+
+  let answer = Answer::builder().ttl_duration(Duration::from_secs(u64::MAX)).build();
+  assert_eq!(answer.ttl(), u32::MAX);
+}
+
+#[test]
+fn test_query_kind_is_meta() {
+  assert!(QueryKind::ALL.is_meta());
+  assert!(QueryKind::AXFR.is_meta());
+  assert!(!QueryKind::A.is_meta());
+  assert!(!QueryKind::OPT.is_meta());
+
+  assert!(QueryKind::OPT.is_pseudo());
+  assert!(!QueryKind::ALL.is_pseudo());
+  assert!(!QueryKind::Reserved(65280).is_pseudo());
+}
+
+#[test]
+fn test_question_new() {
+  parse!(query);
+  let name = query.questions().next().expect("`Questions` iterator is empty").name().clone();
+
+  let mut buf = Message::BUFFER;
+  let mut message = Message::builder(&mut buf)
+    .header(Header::builder().id(7932).recursion_desired(true).build())
+    .build();
+
+  message.add_question(&Question::new(name, QueryKind::A, QueryClass::IN)).expect("adding question failed");
+
+  assert_eq!(message.as_bytes(), &CAPTIVE_APPLE_COM_QUERY[..35]);
+}
+
+#[test]
+fn test_question_write() {
+  let mut name_buf = [0; 32];
+  let name = Name::parse_str("example.com", &mut name_buf).expect("parsing name failed");
+  let mut question = Question::new(name, QueryKind::AAAA, QueryClass::IN);
+
+  let mut buf = [0; 17];
+  assert_eq!(question.write(&mut buf), Ok(17));
+  assert_eq!(buf, [
+    7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', // Label "example"
+    3, b'c', b'o', b'm',                         // Label "com"
+    0,                                           // Label End
+    0, 28,                                       // Question Kind
+    0, 1,                                        // Question Class
+  ]);
+
+  question.set_unicast_response(true);
+  assert_eq!(question.write(&mut buf), Ok(17));
+  assert_eq!(&buf[15..], &[0b10000000, 1]);
+
+  assert_eq!(question.write(&mut [0; 16]), Err(Error::BufferTooSmall));
+}
+
+#[test]
+fn test_answer_write() {
+  let mut buf = WWW_APPLE_COM_CNAME_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+
+  let mut buf = [0; 44];
+  assert_eq!(answer.write(&mut buf), Ok(44));
+  assert_eq!(buf, [
+    3, b'w', b'w', b'w',                         // Label "www"
+    5, b'a', b'p', b'p', b'l', b'e',             // Label "apple"
+    3, b'c', b'o', b'm',                         // Label "com"
+    0,                                           // Label End
+    0, 5,                                        // Answer Kind
+    0, 1,                                        // Answer Class
+    0, 0, 1, 44,                                 // TTL
+    0, 19,                                       // RDATA Length
+    7, b'c', b'a', b'p', b't', b'i', b'v', b'e', // Label "captive"
+    5, b'a', b'p', b'p', b'l', b'e',             // Label "apple"
+    3, b'c', b'o', b'm',                         // Label "com"
+    0,                                           // Label End
+  ]);
+
+  assert_eq!(answer.write(&mut [0; 43]), Err(Error::BufferTooSmall));
+
+  let mut buf = EXAMPLE_COM_SOA_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+
+  let mut buf = [0; 78];
+  assert_eq!(answer.write(&mut buf), Ok(78));
+  assert_eq!(buf, [
+    7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', // Label "example"
+    3, b'c', b'o', b'm',                         // Label "com"
+    0,                                           // Label End
+    0, 6,                                        // Answer Kind
+    0, 1,                                        // Answer Class
+    0, 0, 14, 16,                                // TTL
+    0, 55,                                       // RDATA Length
+    2, b'n', b's',                               // Label "ns"
+    7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', // Label "example"
+    3, b'c', b'o', b'm',                         // Label "com"
+    0,                                           // Label End
+    5, b'a', b'd', b'm', b'i', b'n',             // Label "admin"
+    7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', // Label "example"
+    3, b'c', b'o', b'm',                         // Label "com"
+    0,                                           // Label End
+    120, 150, 169, 225,                          // Serial
+    0, 0, 28, 32,                                // Refresh
+    0, 0, 14, 16,                                // Retry
+    0, 18, 117, 0,                               // Expire
+    0, 0, 14, 16,                                // Minimum
+  ]);
+}
+
+#[test]
+fn test_name_parse_str() {
+  let mut buf = [0; 32];
+  let name = Name::parse_str("captive.apple.com", &mut buf).expect("parsing failed");
+  assert_eq!(&name, "captive.apple.com");
+  assert_eq!(&buf[..19], &CAPTIVE_APPLE_COM_QUERY[12..31]);
+
+  let mut buf = [0; 32];
+  let name = Name::parse_str("captive.apple.com.", &mut buf).expect("parsing failed");
+  assert_eq!(&name, "captive.apple.com");
+
+  let mut buf = [0; 1];
+  let name = Name::parse_str(".", &mut buf).expect("parsing failed");
+  assert_eq!(name.to_string(), "");
+}
+
+#[test]
+fn test_name_parse_str_invalid() {
+  let mut buf = [0; 256];
+  assert!(matches!(Name::parse_str("captive..apple.com", &mut buf), Err(Error::EmptyLabel)));
+  assert!(matches!(Name::parse_str(".captive.apple.com", &mut buf), Err(Error::EmptyLabel)));
+  assert!(matches!(Name::parse_str("captive.apple.com..", &mut buf), Err(Error::EmptyLabel)));
+
+  let label = "a".repeat(64);
+  assert!(matches!(Name::parse_str(&label, &mut buf), Err(Error::LabelTooLong)));
+
+  let name = ["a"; 128].join(".");
+  assert!(matches!(Name::parse_str(&name, &mut buf), Err(Error::NameTooLong)));
+
+  let mut buf = [0; 8];
+  assert!(matches!(Name::parse_str("captive.apple.com", &mut buf), Err(Error::BufferTooSmall)));
+}
+
+#[test]
+fn test_name_parse_str_add_question() {
+  let mut name_buf = [0; 32];
+  let name = Name::parse_str("captive.apple.com", &mut name_buf).expect("parsing failed");
+
+  let mut buf = Message::BUFFER;
+  let mut message = Message::builder(&mut buf)
+    .header(Header::builder().id(7932).recursion_desired(true).build())
+    .build();
+
+  message.add_question(&Question::new(name, QueryKind::A, QueryClass::IN)).expect("adding question failed");
+
+  assert_eq!(message.as_bytes(), &CAPTIVE_APPLE_COM_QUERY[..35]);
+}
+
+#[test]
+fn test_name_labels_str() {
+  let mut buf = WWW_APPLE_COM_CNAME_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+  let name = answer.rdata_name().expect("CNAME target missing");
+
+  assert_eq!(name.labels_str().collect::<Vec<_>>(), ["captive", "apple", "com"]);
+}
+
+#[test]
+fn test_name_write_str() {
+  parse!(request);
+  let name = request.questions().next().expect("`Questions` iterator is empty").name().clone();
+
+  let mut buf = [0; 32];
+  assert_eq!(name.write_str(&mut buf), Ok("captive.apple.com"));
+  assert_eq!(&buf[..17], b"captive.apple.com");
+
+  assert_eq!(name.write_str(&mut [0; 16]), Err(Error::BufferTooSmall));
+  assert_eq!(Name::root().write_str(&mut []), Ok(""));
+
+  let mut name_buf = [0; 32];
+  let name = Name::parse_str(r"a\.b.c", &mut name_buf).expect("parsing name failed");
+  assert_eq!(name.write_str(&mut buf), Ok(r"a\.b.c"));
+}
+
+#[test]
+fn test_name_labels_rev() {
+  let mut buf = [0; 32];
+  let name = Name::parse_str("a.b.c", &mut buf).expect("parsing name failed");
+  assert_eq!(name.labels_rev().collect::<Vec<_>>(), ["c", "b", "a"]);
+
+  // Labels are collected across compression pointers.
+  let mut buf = WWW_APPLE_COM_CNAME_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+  let name = response.answers().next().and_then(|answer| answer.rdata_name()).expect("CNAME target missing");
+  assert_eq!(name.labels_rev().collect::<Vec<_>>(), ["com", "apple", "captive"]);
+
+  assert_eq!(Name::root().labels_rev().next(), None);
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn test_name_to_owned() {
+  let name_buf = {
+    let mut buf = WWW_APPLE_COM_CNAME_RESPONSE;
+    let response = Message::parse(&mut buf).expect("parsing failed");
+
+    let answer = response.answers().next().expect("`Answers` iterator is empty");
+    answer.rdata_name().expect("CNAME target missing").to_owned()
+  };
+
+  assert_eq!(&name_buf.as_name(), "captive.apple.com");
+  assert_eq!(name_buf.to_string(), "captive.apple.com");
+}
+
+#[test]
+fn test_name_eq_hash() {
+  use std::collections::hash_map::DefaultHasher;
+  use std::collections::HashMap;
+  use std::hash::{Hash, Hasher};
+
+  fn hash(name: &Name<'_>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    name.hash(&mut hasher);
+    hasher.finish()
+  }
+
+  let mut buf1 = [0; 16];
+  let name1 = Name::parse_str("Apple.COM", &mut buf1).expect("parsing failed");
+  let mut buf2 = [0; 16];
+  let name2 = Name::parse_str("apple.com", &mut buf2).expect("parsing failed");
+  let mut buf3 = [0; 16];
+  let name3 = Name::parse_str("apple.co", &mut buf3).expect("parsing failed");
+
+  assert_eq!(name1, name2);
+  assert_eq!(hash(&name1), hash(&name2));
+  assert_ne!(name1, name3);
+
+  let mut cache = HashMap::new();
+  cache.insert(name1, 1);
+  assert_eq!(cache.get(&name2), Some(&1));
+  assert_eq!(cache.get(&name3), None);
+}
+
+#[test]
+fn test_name_eq_name() {
+  let mut buf = WWW_APPLE_COM_CNAME_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+
+  let question = response.questions().next().expect("`Questions` iterator is empty");
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+  assert_eq!(question.name(), answer.name());
+
+  let target = answer.rdata_name().expect("CNAME target missing");
+  assert_ne!(question.name(), &target);
+
+  let mut query_buf = CAPTIVE_APPLE_COM_QUERY;
+  let query = Message::parse(&mut query_buf).expect("parsing failed");
+  let query_name = query.questions().next().expect("`Questions` iterator is empty").name().clone();
+
+  assert_eq!(query_name, target);
+  assert_eq!(target, query_name);
+}
+
+#[test]
+fn test_name_label_count_byte_len() {
+  parse!(request);
+  let question = request.questions().next().expect("`Questions` iterator is empty");
+
+  assert_eq!(question.name().label_count(), 3);
+  assert_eq!(question.name().byte_len(), 19);
+
+  let mut buf = WWW_APPLE_COM_CNAME_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+
+  assert_eq!(answer.name().label_count(), 3);
+  assert_eq!(answer.name().byte_len(), 15);
+
+  let mut buf = [0; 1];
+  let root = Name::parse_str("", &mut buf).expect("parsing failed");
+  assert_eq!(root.label_count(), 0);
+  assert_eq!(root.byte_len(), 1);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_header() {
+  parse!(request);
+
+  let json = serde_json::to_value(request.header()).expect("serialization failed");
+  assert_eq!(json["id"], 7932);
+  assert_eq!(json["kind"], "Query");
+  assert_eq!(json["opcode"], "Query");
+  assert_eq!(json["recursion_desired"], true);
+  assert_eq!(json["response_code"], "NoError");
+  assert_eq!(json["question_count"], 1);
+
+  let header: Header = serde_json::from_value(json).expect("deserialization failed");
+  assert_eq!(header.id(), request.header().id());
+  assert_eq!(header.kind(), request.header().kind());
+  assert!(header.recursion_desired());
+  assert_eq!(header.question_count(), 1);
+
+  assert_eq!(serde_json::to_string(&QueryKind::AAAA).unwrap(), r#""AAAA""#);
+  assert_eq!(serde_json::from_str::<QueryClass>(r#""IN""#).unwrap(), QueryClass::IN);
+}
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+#[test]
+fn test_serde_owned_message() {
+  let mut buf = WWW_APPLE_COM_CNAME_RESPONSE;
+  // Set the unicast response bit of the question class and the cache flush bit of the answer class.
+  buf[29] |= 0b10000000;
+  buf[35] |= 0b10000000;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+
+  let owned = OwnedMessage::from(&response);
+  let json = serde_json::to_value(&owned).expect("serialization failed");
+
+  assert_eq!(json["questions"][0]["name"], "www.apple.com");
+  assert_eq!(json["questions"][0]["kind"], "A");
+  assert_eq!(json["questions"][0]["class"], "IN");
+  assert_eq!(json["questions"][0]["unicast_response"], true);
+  assert_eq!(json["answers"][0]["name"], "www.apple.com");
+  assert_eq!(json["answers"][0]["kind"], "CNAME");
+  assert_eq!(json["answers"][0]["class"], "IN");
+  assert_eq!(json["answers"][0]["ttl"], 300);
+  assert_eq!(json["answers"][0]["cache_flush"], true);
+
+  let owned: OwnedMessage = serde_json::from_value(json).expect("deserialization failed");
+  assert!(owned.questions[0].unicast_response);
+  assert!(owned.answers[0].cache_flush);
+  assert_eq!(owned.answers[0].rdata, &WWW_APPLE_COM_CNAME_RESPONSE[43..]);
+}
+
+#[test]
+fn test_message_display() {
+  parse!(request);
+
+  assert_eq!(request.to_string(), "\
+;; ->>HEADER<<- opcode: QUERY, status: NOERROR, id: 7932
+;; flags: rd; QUERY: 1, ANSWER: 0, AUTHORITY: 0, ADDITIONAL: 0
+
+;; QUESTION SECTION:
+;captive.apple.com.\tIN\tA
+");
+
+  let mut buf = WWW_APPLE_COM_CNAME_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+
+  assert_eq!(response.to_string(), "\
+;; ->>HEADER<<- opcode: QUERY, status: NOERROR, id: 7932
+;; flags: qr rd ra; QUERY: 1, ANSWER: 1, AUTHORITY: 0, ADDITIONAL: 0
+
+;; QUESTION SECTION:
+;www.apple.com.\tIN\tA
+
+;; ANSWER SECTION:
+www.apple.com.\t300\tIN\tCNAME\tcaptive.apple.com.
+");
+
+  let mut buf = EXAMPLE_COM_TXT_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+
+  assert!(response.to_string().ends_with("example.com.\t300\tIN\tTXT\t\\# 13 06763d7370663100042d616c6c\n"));
+}
+
+#[test]
+fn test_message_eq_ignoring_id() {
+  let mut buf = WWW_APPLE_COM_CNAME_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+
+  let question = response.questions().next().expect("`Questions` iterator is empty");
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+
+  // Rebuild the response without compressing the answer name.
+  let mut uncompressed = [0; 66];
+  uncompressed[..31].copy_from_slice(&WWW_APPLE_COM_CNAME_RESPONSE[..31]);
+  uncompressed[31..46].copy_from_slice(&WWW_APPLE_COM_CNAME_RESPONSE[12..27]);
+  uncompressed[46..].copy_from_slice(&WWW_APPLE_COM_CNAME_RESPONSE[33..]);
+  uncompressed[1] = 1;
+  let rebuilt = Message::parse(&mut uncompressed).expect("parsing failed");
+
+  assert_ne!(rebuilt.as_bytes(), response.as_bytes());
+  assert!(rebuilt.eq_ignoring_id(&response));
+  assert!(response.eq_ignoring_id(&rebuilt));
+
+  let mut buf = Message::BUFFER;
+  let mut other = Message::builder(&mut buf).header(response.header().clone()).build();
+  unsafe {
+    other.header_mut().set_question_count(0);
+    other.header_mut().set_answer_count(0);
+  }
+  other.add_question(&question).expect("adding question failed");
+  assert!(!other.eq_ignoring_id(&response));
+
+  other.add_answer(&Answer::builder().name(answer.name().clone()).kind(QueryKind::CNAME).ttl(299).rdata(&CAPTIVE_APPLE_COM_QUERY[12..31]).build()).expect("adding answer failed");
+  assert!(!other.eq_ignoring_id(&response));
+
+  let mut buf = EXAMPLE_COM_SOA_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+  let question = response.questions().next().expect("`Questions` iterator is empty");
+
+  // Rebuild the response with uncompressed names in the SOA record.
+  let mut buf = Message::BUFFER;
+  let mut rebuilt = Message::builder(&mut buf).header(response.header().clone()).build();
+  unsafe {
+    rebuilt.header_mut().set_question_count(0);
+    rebuilt.header_mut().set_answer_count(0);
+  }
+  rebuilt.add_question(&question).expect("adding question failed");
+  rebuilt.append_answers_from(&response).expect("appending answers failed");
+
+  assert_ne!(rebuilt.as_bytes(), response.as_bytes());
+  assert!(rebuilt.eq_ignoring_id(&response));
+  assert!(response.eq_ignoring_id(&rebuilt));
+}
+
+#[test]
+fn test_parse_ref() {
+  let request = Message::parse_ref(&CAPTIVE_APPLE_COM_QUERY).expect("parsing failed");
+  assert_eq!(request.len(), 35);
+  assert_eq!(request.header().id(), 7932);
+
+  let question = request.questions().next().expect("`Questions` iterator is empty");
+  assert_eq!(question.name(), "captive.apple.com");
+
+  parse!(mutable_request);
+  assert!(request.eq_ignoring_id(&mutable_request));
+  assert_eq!(request.to_string(), mutable_request.to_string());
+
+  assert_eq!(Message::parse_ref(&CAPTIVE_APPLE_COM_QUERY[..20]).err(), Some(Error::MessageTooShort { offset: 20 }));
+}
+
+#[test]
+fn test_message_size_consts() {
+  assert_eq!(Message::HEADER_SIZE, 12);
+  assert_eq!(Message::MAX_UDP_PAYLOAD, 512);
+  assert_eq!(Message::BUFFER.len(), Message::MAX_UDP_PAYLOAD);
+  assert_eq!(core::mem::size_of::<MessageBuffer>(), Message::MAX_UDP_PAYLOAD);
+}
+
+#[test]
+fn test_parse_with_limit() {
+  let mut name_buf = [0; 32];
+  let name = Name::parse_str("example.com", &mut name_buf).expect("parsing name failed");
+  let rdata = [0; 200];
+
+  let mut buf = [0; 1500];
+  let mut message = Message::builder(&mut buf).build();
+  while message.add_answer(&Answer::builder().name(name.clone()).kind(QueryKind::NULL).rdata(&rdata).build()).is_ok() {}
+  assert_eq!(message.header().answer_count(), 6);
+  let len = message.len();
+  assert!(len > Message::MAX_UDP_PAYLOAD);
+
+  let mut bytes = [0; 1500];
+  bytes[..len].copy_from_slice(message.as_bytes());
+  assert_eq!(Message::parse(&mut bytes).err(), Some(Error::MessageTooLong));
+
+  let parsed = Message::parse_with_limit(&mut bytes, 1500).expect("parsing failed");
+  assert_eq!(parsed.len(), len);
+  assert_eq!(parsed.answers().count(), 6);
+
+  // Messages received over TCP are only limited by their length prefix.
+  let mut tcp_buf = [0; 1502];
+  let tcp_len = message.write_tcp(&mut tcp_buf).expect("writing failed");
+  let (parsed, consumed) = Message::parse_tcp(&mut tcp_buf).expect("parsing failed");
+  assert_eq!(consumed, tcp_len);
+  assert_eq!(parsed.answers().count(), 6);
+}
+
+#[test]
+fn test_parse_tcp() {
+  let mut buf = [0; 40];
+  buf[..2].copy_from_slice(&35u16.to_be_bytes());
+  buf[2..37].copy_from_slice(&CAPTIVE_APPLE_COM_QUERY[..35]);
+
+  let (request, consumed) = Message::parse_tcp(&mut buf).expect("parsing failed");
+  assert_eq!(consumed, 37);
+  assert_eq!(request.as_bytes(), &CAPTIVE_APPLE_COM_QUERY[..35]);
+
+  assert_eq!(Message::parse_tcp(&mut buf[..36]).err(), Some(Error::MessageTooShort { offset: 2 }));
+  assert_eq!(Message::parse_tcp(&mut buf[..1]).err(), Some(Error::MessageTooShort { offset: 0 }));
+}
+
+#[test]
+fn test_parse_stream() {
+  parse!(request);
+
+  let mut buf = [0; 80];
+  let mut len = request.write_tcp(&mut buf).expect("writing failed");
+  len += request.write_tcp(&mut buf[len..]).expect("writing failed");
+  assert_eq!(len, 74);
+
+  // Partial third frame.
+  buf[74..76].copy_from_slice(&35u16.to_be_bytes());
+
+  let mut stream = Message::parse_stream(&mut buf);
+  let first = stream.next().expect("stream is empty").expect("parsing failed");
+  assert_eq!(first.as_bytes(), request.as_bytes());
+  let second = stream.next().expect("stream is empty").expect("parsing failed");
+  assert_eq!(second.as_bytes(), request.as_bytes());
+  assert!(stream.next().is_none());
+  assert_eq!(stream.remaining(), 6);
+
+  let mut buf = [0, 2, 0, 0];
+  let mut stream = Message::parse_stream(&mut buf);
+  assert_eq!(stream.next().map(|m| m.err()), Some(Some(Error::MessageTooShort { offset: 0 })));
+  assert!(stream.next().is_none());
+  assert_eq!(stream.remaining(), 0);
+}
+
+#[test]
+fn test_write_tcp() {
+  parse!(request);
+
+  let mut buf = [0; 64];
+  let len = request.write_tcp(&mut buf).expect("writing failed");
+  assert_eq!(len, 37);
+  assert_eq!(&buf[..2], &[0, 35]);
+
+  let (parsed, consumed) = Message::parse_tcp(&mut buf).expect("parsing failed");
+  assert_eq!(consumed, len);
+  assert_eq!(parsed.as_bytes(), request.as_bytes());
+
+  let mut buf = [0; 36];
+  assert!(matches!(request.write_tcp(&mut buf), Err(Error::BufferTooSmall)));
+}
+
+#[test]
+fn test_add_record_buffer_full() {
+  let mut name_buf = [0; 32];
+  let name = Name::parse_str("captive.apple.com", &mut name_buf).expect("parsing failed");
+  let mut other_name_buf = [0; 32];
+  let other_name = Name::parse_str("captive.apple.org", &mut other_name_buf).expect("parsing failed");
+
+  let mut buf = [0; 60];
+  let mut message = Message::builder(&mut buf).build();
+
+  message.add_question(&Question::new(name.clone(), QueryKind::A, QueryClass::IN)).expect("adding question failed");
+  message.add_answer(&Answer::builder().name(name).rdata(&[192, 0, 2, 1]).build()).expect("adding answer failed");
+  assert_eq!(message.len(), 51);
+
+  let before = message.to_vec();
+
+  assert!(matches!(
+    message.add_question(&Question::new(other_name.clone(), QueryKind::A, QueryClass::IN)),
+    Err(Error::MessageTooLong)
+  ));
+  assert!(matches!(
+    message.add_answer(&Answer::builder().name(other_name).rdata(&[192, 0, 2, 1]).build()),
+    Err(Error::MessageTooLong)
+  ));
+
+  assert_eq!(message.as_bytes(), &before[..]);
+  assert_eq!(message.header().question_count(), 1);
+  assert_eq!(message.header().answer_count(), 1);
+  assert_eq!(message.answers().next().expect("`Answers` iterator is empty").as_ipv4(), Some([192, 0, 2, 1].into()));
+}
+
+const SUB_EXAMPLE_COM_NS_RESPONSE: [u8; 50] = [
+  30, 252,                                     // ID
+  129, 128,                                    // Kind
+  0, 1,                                        // Question Count
+  0, 0,                                        // Answer Count
+  0, 1,                                        // Name Server Count
+  0, 0,                                        // Additional Records Count
+  7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', // Label "example"
+  3, b'c', b'o', b'm',                         // Label "com"
+  0,                                           // Label End
+  0, 1,                                        // Question Kind
+  0, 1,                                        // Question Class
+  3, b's', b'u', b'b',                         // Label "sub"
+  0b11000000, 12,                              // Pointer to "example.com"
+  0, 2,                                        // Name Server Kind
+  0, 1,                                        // Name Server Class
+  0, 0, 14, 16,                                // TTL
+  0, 5,                                        // RDATA Length
+  2, b'n', b's',                               // Label "ns"
+  0b11000000, 29,                              // Pointer to "sub.example.com"
+];
+
+#[test]
+fn test_add_answer_shifts_rdata_pointers() {
+  let mut buf = Message::BUFFER;
+  buf[..50].copy_from_slice(&SUB_EXAMPLE_COM_NS_RESPONSE);
+  let mut response = Message::parse(&mut buf).expect("parsing failed");
+
+  let mut name_buf = [0; 32];
+  let name = Name::parse_str("example.com", &mut name_buf).expect("parsing name failed");
+  response.add_answer(&Answer::builder().name(name).rdata(&[192, 0, 2, 1]).build()).expect("adding answer failed");
+
+  let mut bytes = response.to_vec();
+  let response = Message::parse(&mut bytes).expect("parsing failed");
+  let name_server = response.name_servers().next().expect("`Answers` iterator is empty");
+  assert_eq!(name_server.name(), "sub.example.com");
+  assert_eq!(&name_server.rdata_name().expect("invalid NS record"), "ns.sub.example.com");
+}
+
+#[test]
+fn test_add_answer_compressed_rdata() {
+  let mut buf = WWW_APPLE_COM_CNAME_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+
+  let mut name_buf = [0; 32];
+  let name = Name::parse_str("x.org", &mut name_buf).expect("parsing name failed");
+  let mut buf = Message::BUFFER;
+  let mut message = Message::builder(&mut buf).build();
+  message.add_question(&Question::new(name, QueryKind::CNAME, QueryClass::IN)).expect("adding question failed");
+
+  // The CNAME target is compressed using a pointer into `response`.
+  message.add_answer(&answer).expect("adding answer failed");
+
+  let answer = message.answers().next().expect("`Answers` iterator is empty");
+  assert_eq!(answer.name(), "www.apple.com");
+  assert_eq!(&answer.rdata_name().expect("invalid CNAME record"), "captive.apple.com");
+}
+
+#[test]
+fn test_add_answer_pointer_out_of_range() {
+  let mut name_buf = [0; 32];
+  let pad = Name::parse_str("pad", &mut name_buf).expect("parsing name failed");
+  let mut name_buf = [0; 32];
+  let x = Name::parse_str("x", &mut name_buf).expect("parsing name failed");
+  let mut name_buf = [0; 32];
+  let y = Name::parse_str("y", &mut name_buf).expect("parsing name failed");
+
+  // Place "x" and "y" at 0x3FEA and 0x3FFB, close to the largest offset reachable by a pointer.
+  let padding = vec![0; 0x3FEA - 12 - 15];
+  let mut buf = vec![0; 0x4100];
+  let mut message = Message::builder(&mut buf)
+    .answer(&Answer::builder().name(pad.clone()).kind(QueryKind::NULL).rdata(&padding).build()).expect("adding answer failed")
+    .name_server(&Answer::builder().name(x.clone()).rdata(&[192, 0, 2, 1]).build()).expect("adding name server failed")
+    .name_server(&Answer::builder().name(y.clone()).rdata(&[192, 0, 2, 2]).build()).expect("adding name server failed")
+    .additional_record(&Answer::builder().name(x).rdata(&[192, 0, 2, 1]).build()).expect("adding additional record failed")
+    .additional_record(&Answer::builder().name(y).rdata(&[192, 0, 2, 2]).build()).expect("adding additional record failed")
+    .build();
+
+  let before = message.to_vec();
+
+  // Only the pointer to "y" would be moved out of range.
+  assert!(matches!(
+    message.add_answer(&Answer::builder().name(pad).rdata(&[192, 0, 2, 3]).build()),
+    Err(Error::Pointer { .. })
+  ));
+
+  assert_eq!(message.as_bytes(), &before[..]);
+  assert_eq!(message.header().answer_count(), 1);
+
+  let mut additional_records = message.additional_records();
+  assert_eq!(additional_records.next().expect("`Answers` iterator is empty").name(), "x");
+  assert_eq!(additional_records.next().expect("`Answers` iterator is empty").name(), "y");
+}
+
+#[test]
+fn test_truncate_for_udp() {
+  parse!(query);
+  let question = query.questions().next().expect("`Questions` iterator is empty");
+
+  let mut buf = [0; 1024];
+  let mut response = Message::builder(&mut buf)
+    .header(Header::builder().id(query.header().id()).kind(HeaderKind::Response).build())
+    .build();
+
+  response.add_question(&question).expect("adding question failed");
+  for i in 0..40 {
+    let rdata = [192, 0, 2, i];
+    let answer = Answer::builder().name(question.name().clone()).rdata(&rdata).build();
+    response.add_answer(&answer).expect("adding answer failed");
+  }
+  assert_eq!(response.len(), 35 + 40 * 16);
+  assert!(!response.header().truncated());
+
+  response.truncate_for_udp();
+
+  assert!(response.header().truncated());
+  assert!(response.len() <= 512);
+  assert_eq!(response.header().answer_count(), 29);
+  assert_eq!(response.len(), 35 + 29 * 16);
+
+  let answers = response.answers().map(|answer| answer.as_ipv4().unwrap().octets()[3]).collect::<Vec<_>>();
+  assert_eq!(answers, (0..29).collect::<Vec<_>>());
+
+  let mut bytes = response.to_vec();
+  assert!(Message::parse(&mut bytes).is_ok());
+}
+
+#[test]
+fn test_respond() {
+  parse!(query);
+
+  let mut buf = Message::BUFFER;
+  let response = Message::respond(&query, &mut buf).expect("responding failed");
+
+  assert_eq!(response.header().id(), query.header().id());
+  assert_eq!(response.header().kind(), HeaderKind::Response);
+  assert_eq!(response.header().opcode(), OpCode::Query);
+  assert!(response.header().recursion_desired());
+  assert_eq!(response.header().question_count(), 1);
+  assert_eq!(response.header().answer_count(), 0);
+
+  let question = response.questions().next().expect("`Questions` iterator is empty");
+  let query_question = query.questions().next().expect("`Questions` iterator is empty");
+  assert_eq!(question.name(), query_question.name());
+  assert_eq!(question.kind(), query_question.kind());
+  assert_eq!(question.class(), query_question.class());
+  assert_eq!(&response[12..], &query[12..]);
+}
+
+#[test]
+fn test_header_new() {
+  let header = Header::new();
+
+  assert_eq!(header.id(), 0);
+  assert_eq!(header.kind(), HeaderKind::Query);
+  assert_eq!(header.opcode(), OpCode::Query);
+  assert!(!header.authoritative_answer());
+  assert!(!header.truncated());
+  assert!(!header.recursion_desired());
+  assert!(!header.recursion_available());
+  assert_eq!(header.response_code(), ResponseCode::NoError);
+  assert_eq!(header.question_count(), 0);
+  assert_eq!(header.answer_count(), 0);
+  assert_eq!(header.name_server_count(), 0);
+  assert_eq!(header.additional_records_count(), 0);
+
+  parse!(request);
+  let mut buf = CAPTIVE_APPLE_COM_QUERY;
+  let mut message = Message::parse(&mut buf).expect("parsing failed");
+  *message.header_mut() = Header::new();
+  assert_ne!(message.header().id(), request.header().id());
+  assert_eq!(message.questions().count(), 0);
+}
+
+#[test]
+fn test_header_eq() {
+  parse!(request);
+
+  let header = request.header().clone();
+  assert_eq!(&header, request.header());
+
+  let mut other = header.clone();
+  other.set_id(1);
+  assert_ne!(other, header);
+
+  assert_eq!(Header::new(), Header::builder().build());
+}
+
+#[test]
+fn test_pointer_target_past_pointer() {
+  // Found by fuzzing: the question name points to offset 0, where a length byte of 30
+  // claims a label which extends past the pointer and beyond the end of the message.
+  let mut buf = [
+    30, 252, 129, 128, 0, 1, 0, 0, 0, 0, 0, 0,
+    7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 3, 227, b'o', b'm', 0b11000000, 0,
+    0, 16, 0, 1,
+    0b11000000, 12, 0, 16, 0, 3, 0, 0, 1, 44, 0, 13,
+    6, 0b11000000, 61, b's', b'p', b'f', b'1', 0, 4, 45, b'a', b'l', b'l',
+  ];
+
+  assert!(matches!(Message::parse(&mut buf), Err(Error::Pointer { .. })));
+}
+
+#[test]
+fn test_error_offset() {
+  let mut buf = CAPTIVE_APPLE_COM_QUERY;
+
+  // Truncate within the question class.
+  let error = Message::parse_ref(&buf[..34]).expect_err("parsing succeeded");
+  assert_eq!(error, Error::MessageTooShort { offset: 33 });
+  assert_eq!(error.to_string(), "message too short at offset 33");
+
+  // Replace the first label with a pointer to itself.
+  buf[12] = 0b11000000;
+  buf[13] = 12;
+  let error = Message::parse_ref(&buf).expect_err("parsing succeeded");
+  assert_eq!(error, Error::Pointer { offset: 12 });
+  assert_eq!(error.to_string(), "invalid pointer at offset 12");
+}
+
+#[test]
+fn test_pointer_into_header() {
+  let mut buf = WWW_APPLE_COM_CNAME_RESPONSE;
+
+  // Point the answer name at offset 3, within the header.
+  buf[32] = 3;
+  let error = Message::parse_ref(&buf).expect_err("parsing succeeded");
+  assert_eq!(error, Error::Pointer { offset: 31 });
+}
+
+#[test]
+fn test_query_kind_str() {
+  assert_eq!("a".parse::<QueryKind>(), Ok(QueryKind::A));
+  assert_eq!("AAAA".parse::<QueryKind>(), Ok(QueryKind::AAAA));
+  assert_eq!("any".parse::<QueryKind>(), Ok(QueryKind::ALL));
+  assert_eq!("type15".parse::<QueryKind>(), Ok(QueryKind::MX));
+  assert_eq!("TYPE65280".parse::<QueryKind>(), Ok(QueryKind::Reserved(65280)));
+  assert_eq!("AAAAA".parse::<QueryKind>(), Err(Error::UnknownQueryKind));
+  assert_eq!("TYPE".parse::<QueryKind>(), Err(Error::UnknownQueryKind));
+
+  assert_eq!(QueryKind::MX.to_string(), "MX");
+  assert_eq!(QueryKind::from(65280).to_string(), "TYPE65280");
+  assert_eq!("TYPE65280".parse::<QueryKind>().map(u16::from), Ok(65280));
+
+  assert_eq!(QueryKind::from(253), QueryKind::MAILB);
+  assert_eq!(QueryKind::from(254), QueryKind::MAILA);
+}
+
+#[test]
+fn test_query_class_str() {
+  for class in [QueryClass::IN, QueryClass::CS, QueryClass::CH, QueryClass::HS, QueryClass::Reserved(254)].iter() {
+    assert_eq!(class.to_string().parse::<QueryClass>(), Ok(*class));
+    assert_eq!(class.to_string().to_lowercase().parse::<QueryClass>(), Ok(*class));
+  }
+
+  assert_eq!("IN".parse::<QueryClass>(), Ok(QueryClass::IN));
+  assert_eq!(QueryClass::CH.to_string(), "CH");
+  assert_eq!(QueryClass::from(254).to_string(), "CLASS254");
+  assert_eq!("CLASS1".parse::<QueryClass>(), Ok(QueryClass::IN));
+  assert_eq!("INTERNET".parse::<QueryClass>(), Err(Error::UnknownQueryClass));
+}
+
+#[test]
+fn test_opcode_display() {
+  assert_eq!(OpCode::Query.to_string(), "QUERY");
+  assert_eq!(OpCode::InverseQuery.to_string(), "IQUERY");
+  assert_eq!(OpCode::Update.to_string(), "UPDATE");
+  assert_eq!(OpCode::Reserved(3).to_string(), "RESERVED3");
+}
+
+#[test]
+fn test_response_code_display() {
+  assert_eq!(ResponseCode::NoError.to_string(), "NOERROR");
+  assert_eq!(ResponseCode::ServerFailure.to_string(), "SERVFAIL");
+  assert_eq!(ResponseCode::NonExistentDomain.to_string(), "NXDOMAIN");
+  assert_eq!(ResponseCode::from(12).to_string(), "RCODE12");
+}
+
+#[test]
+fn test_message_builder_question() {
+  let mut name_buf = [0; 32];
+  let name = Name::parse_str("captive.apple.com", &mut name_buf).expect("parsing name failed");
+
+  let mut buf = Message::BUFFER;
+  let request = Message::builder(&mut buf)
+    .header(Header::builder().id(7932).recursion_desired(true).build())
+    .question(&Question::new(name, QueryKind::A, QueryClass::IN)).expect("adding question failed")
+    .build();
+
+  assert_eq!(request.as_bytes(), &CAPTIVE_APPLE_COM_QUERY[..35]);
+
+  let mut buf = Message::BUFFER;
+  let request = Message::builder(&mut buf)
+    .question(&request.questions().next().expect("`Questions` iterator is empty")).expect("adding question failed")
+    .header(Header::builder().id(7932).recursion_desired(true).build())
+    .build();
+
+  assert_eq!(request.header().question_count(), 1);
+  assert_eq!(request.as_bytes(), &CAPTIVE_APPLE_COM_QUERY[..35]);
+}
+
+#[test]
+fn test_message_builder_answer() {
+  parse!(query);
+  let question = query.questions().next().expect("`Questions` iterator is empty");
+
+  let answer = Answer::builder()
+    .name(question.name().clone())
+    .kind(QueryKind::A)
+    .ttl(300)
+    .rdata(&[192, 0, 2, 1])
+    .build();
+
+  let mut buf = Message::BUFFER;
+  let response = Message::builder(&mut buf)
+    .header(Header::builder().id(query.header().id()).kind(HeaderKind::Response).build())
+    .question(&question).expect("adding question failed")
+    .answer(&answer).expect("adding answer failed")
+    .build();
+
+  assert_eq!(response.header().question_count(), 1);
+  assert_eq!(response.header().answer_count(), 1);
+
+  let mut buf = Message::BUFFER;
+  let reordered = Message::builder(&mut buf)
+    .header(Header::builder().id(query.header().id()).kind(HeaderKind::Response).build())
+    .answer(&answer).expect("adding answer failed")
+    .question(&question).expect("adding question failed")
+    .build();
+
+  // Questions are still placed before answers.
+  assert!(reordered.eq_ignoring_id(&response));
+
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+  assert_eq!(answer.name(), "captive.apple.com");
+  assert_eq!(answer.as_ipv4(), Some(core::net::Ipv4Addr::new(192, 0, 2, 1)));
+}
+
+#[test]
+fn test_message_builder_parsed_answer() {
+  // The RDATA names of both records are compressed using pointers into their own message.
+  let mut cname_buf = WWW_APPLE_COM_CNAME_RESPONSE;
+  let cname_response = Message::parse(&mut cname_buf).expect("parsing failed");
+  let cname = cname_response.answers().next().expect("`Answers` iterator is empty");
+  let mut ns_buf = WWW_EXAMPLE_COM_NS_RESPONSE;
+  let ns_response = Message::parse(&mut ns_buf).expect("parsing failed");
+  let ns = ns_response.name_servers().next().expect("`Answers` iterator is empty");
+
+  let mut name_buf = [0; 32];
+  let name = Name::parse_str("x.org", &mut name_buf).expect("parsing name failed");
+
+  let mut buf = Message::BUFFER;
+  let message = Message::builder(&mut buf)
+    .question(&Question::new(name, QueryKind::CNAME, QueryClass::IN)).expect("adding question failed")
+    .answer(&cname).expect("adding answer failed")
+    .name_server(&ns).expect("adding name server failed")
+    .build();
+
+  let mut bytes = message.to_vec();
+  let message = Message::parse_strict(&mut bytes).expect("parsing failed");
+
+  let answer = message.answers().next().expect("`Answers` iterator is empty");
+  assert_eq!(answer.name(), "www.apple.com");
+  assert_eq!(&answer.rdata_name().expect("invalid CNAME record"), "captive.apple.com");
+
+  let name_server = message.name_servers().next().expect("`Answers` iterator is empty");
+  assert_eq!(name_server.name(), "example.org");
+  assert_eq!(&name_server.rdata_name().expect("invalid NS record"), "www.example.com");
+}
+
+#[test]
+fn test_remove_last_record() {
+  let mut name_buf = [0; 32];
+  let first = Name::parse_str("captive.apple.com", &mut name_buf).expect("parsing name failed");
+  let mut name_buf = [0; 32];
+  let second = Name::parse_str("example.com", &mut name_buf).expect("parsing name failed");
+
+  let mut buf = Message::BUFFER;
+  let mut message = Message::builder(&mut buf)
+    .header(Header::builder().id(7932).recursion_desired(true).build())
+    .question(&Question::new(first.clone(), QueryKind::A, QueryClass::IN)).expect("adding question failed")
+    .question(&Question::new(second, QueryKind::A, QueryClass::IN)).expect("adding question failed")
+    .build();
+
+  assert!(message.remove_last_question());
+  assert_eq!(message.header().question_count(), 1);
+  assert_eq!(message.as_bytes(), &CAPTIVE_APPLE_COM_QUERY[..35]);
+
+  let mut questions = message.questions();
+  assert_eq!(questions.next().expect("`Questions` iterator is empty").name(), "captive.apple.com");
+  assert!(questions.next().is_none());
+
+  // The answer name is compressed using a pointer to the question.
+  message.add_answer(&Answer::builder().name(first).rdata(&[192, 0, 2, 1]).build()).expect("adding answer failed");
+  assert!(!message.remove_last_question());
+
+  assert!(message.remove_last_answer());
+  assert!(!message.remove_last_answer());
+  assert!(message.remove_last_question());
+  assert!(!message.remove_last_question());
+  assert_eq!(message.as_bytes().len(), 12);
+}
+
+const WWW_EXAMPLE_COM_NS_RESPONSE: [u8; 74] = [
+  30, 252,                                     // ID
+  129, 128,                                    // Kind
+  0, 1,                                        // Question Count
+  0, 1,                                        // Answer Count
+  0, 1,                                        // Name Server Count
+  0, 0,                                        // Additional Records Count
+  7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', // Label "example"
+  3, b'c', b'o', b'm',                         // Label "com"
+  0,                                           // Label End
+  0, 1,                                        // Question Kind
+  0, 1,                                        // Question Class
+  3, b'w', b'w', b'w',                         // Label "www"
+  0b11000000, 12,                              // Pointer to "example.com"
+  0, 1,                                        // Answer Kind
+  0, 1,                                        // Answer Class
+  0, 0, 14, 16,                                // TTL
+  0, 4,                                        // RDATA Length
+  192, 0, 2, 1,                                // IP Address
+  7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', // Label "example"
+  3, b'o', b'r', b'g',                         // Label "org"
+  0,                                           // Label End
+  0, 2,                                        // Name Server Kind
+  0, 1,                                        // Name Server Class
+  0, 0, 14, 16,                                // TTL
+  0, 2,                                        // RDATA Length
+  0b11000000, 29,                              // Pointer to "www.example.com"
+];
+
+#[test]
+fn test_remove_last_answer_rdata_pointers() {
+  let mut buf = Message::BUFFER;
+  buf[..50].copy_from_slice(&SUB_EXAMPLE_COM_NS_RESPONSE);
+  let mut message = Message::parse(&mut buf).expect("parsing failed");
+
+  let mut name_buf = [0; 32];
+  let name = Name::parse_str("example.com", &mut name_buf).expect("parsing name failed");
+  message.add_answer(&Answer::builder().name(name).rdata(&[192, 0, 2, 1]).build()).expect("adding answer failed");
+
+  // The NS target "ns.sub.example.com" points to the name server record itself.
+  assert!(message.remove_last_answer());
+  assert_eq!(message.as_bytes(), &SUB_EXAMPLE_COM_NS_RESPONSE[..]);
+
+  // The NS target "www.example.com" points into the answer.
+  let mut buf = WWW_EXAMPLE_COM_NS_RESPONSE;
+  let mut message = Message::parse(&mut buf).expect("parsing failed");
+  assert!(!message.remove_last_answer());
+  assert_eq!(message.as_bytes(), &WWW_EXAMPLE_COM_NS_RESPONSE[..]);
+}
+
+#[test]
+fn test_message_clear() {
+  let mut buf = CAPTIVE_APPLE_COM_AAAA_RESPONSE;
+  let mut response = Message::parse(&mut buf).expect("parsing failed");
+  let header = response.header().clone();
+
+  response.clear();
+
+  assert_eq!(response.as_bytes().len(), 12);
+  assert!(response.questions().next().is_none());
+  assert!(response.answers().next().is_none());
+  assert_eq!(response.header().id(), header.id());
+  assert_eq!(response.header().kind(), header.kind());
+  assert_eq!(response.header().recursion_desired(), header.recursion_desired());
+  assert_eq!(response.header().question_count(), 0);
+  assert_eq!(response.header().answer_count(), 0);
+}
+
+#[test]
+fn test_header_as_bytes() {
+  let header = Header::builder()
+    .id(0x1234)
+    .kind(HeaderKind::Response)
+    .recursion_desired(true)
+    .recursion_available(true)
+    .response_code(ResponseCode::NonExistentDomain)
+    .build();
+
+  assert_eq!(header.as_bytes(), &[
+    0x12, 0x34, // ID
+    0x81, 0x83, // Kind
+    0, 0,       // Question Count
+    0, 0,       // Answer Count
+    0, 0,       // Name Server Count
+    0, 0,       // Additional Records Count
+  ]);
+
+  parse!(request);
+  assert_eq!(request.header().as_bytes(), &CAPTIVE_APPLE_COM_QUERY[..12]);
+}
+
+#[test]
+fn test_header_random_id() {
+  let mut ids = [0x1234, 0x5678].iter().copied();
+  let header = Header::builder().random_id_with(|| ids.next().unwrap()).build();
+  assert_eq!(header.id(), 0x1234);
+
+  #[cfg(feature = "getrandom")]
+  {
+    // Two random IDs are unlikely to be equal 16 times in a row.
+    let distinct = (0..16).any(|_| {
+      let a = Header::builder().random_id().expect("generating ID failed").build();
+      let b = Header::builder().random_id().expect("generating ID failed").build();
+      a.id() != b.id()
+    });
+    assert!(distinct);
+  }
+}
+
+#[test]
+fn test_header_sequential_id() {
+  use core::sync::atomic::AtomicU16;
+
+  let counter = AtomicU16::new(u16::MAX - 1);
+  let ids = [
+    Header::builder().sequential_id(&counter).build().id(),
+    Header::builder().sequential_id(&counter).build().id(),
+    Header::builder().sequential_id(&counter).build().id(),
+  ];
+  assert_eq!(ids, [u16::MAX - 1, u16::MAX, 0]);
+}
+
+#[test]
+fn test_answer_name_compression() {
+  let mut name_buf = [0; 32];
+  let name = Name::parse_str("example.com", &mut name_buf).expect("parsing name failed");
+
+  let answer = Answer::builder().name(name).rdata(&[192, 0, 2, 1]).build();
+
+  let mut buf = Message::BUFFER;
+  let mut message = Message::builder(&mut buf)
+    .answer(&answer).expect("adding answer failed")
+    .answer(&answer).expect("adding answer failed")
+    .build();
+
+  assert_eq!(&message.as_bytes()[12..25], &[7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 3, b'c', b'o', b'm', 0]);
+  assert_eq!(&message.as_bytes()[39..41], &[0b11000000, 12]);
+  assert_eq!(message.as_bytes().len(), 12 + 27 + 16);
+
+  // Inserting a question moves the pointer in the second answer.
+  let mut name_buf = [0; 32];
+  let other_name = Name::parse_str("captive.apple.org", &mut name_buf).expect("parsing name failed");
+  message.add_question(&Question::new(other_name, QueryKind::A, QueryClass::IN)).expect("adding question failed");
+
+  for answer in message.answers() {
+    assert_eq!(answer.name(), "example.com");
+  }
+
+  assert!(message.remove_last_question());
+
+  assert_eq!(&message.as_bytes()[39..41], &[0b11000000, 12]);
+  for answer in message.answers() {
+    assert_eq!(answer.name(), "example.com");
+  }
+}
+
+#[test]
+fn test_exact_size_iterators() {
+  let mut buf = CAPTIVE_APPLE_COM_AAAA_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+
+  let mut questions = response.questions();
+  assert_eq!(questions.len(), 1);
+  questions.next();
+  assert_eq!(questions.len(), 0);
+  assert!(questions.next().is_none());
+  assert_eq!(questions.size_hint(), (0, Some(0)));
+
+  let mut answers = response.answers();
+  assert_eq!(answers.len(), 1);
+  answers.next();
+  assert_eq!(answers.len(), 0);
+}
+
+#[test]
+fn test_name_from_reverse() {
+  use core::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+  let mut buf = [0; 128];
+  let name = Name::from_reverse(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1)), &mut buf).expect("creating name failed");
+  assert_eq!(name.to_string(), "1.2.0.192.in-addr.arpa");
+  assert_eq!(name.byte_len(), 24);
+
+  let mut buf = [0; 128];
+  let name = Name::from_reverse(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 255)), &mut buf).expect("creating name failed");
+  assert_eq!(name.to_string(), "255.0.0.10.in-addr.arpa");
+
+  let mut buf = [0; 128];
+  let addr = Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0x567);
+  let name = Name::from_reverse(IpAddr::V6(addr), &mut buf).expect("creating name failed");
+  assert_eq!(name.to_string(), "7.6.5.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa");
+  assert_eq!(name.byte_len(), 74);
+
+  let mut buf = [0; 73];
+  assert_eq!(Name::from_reverse(IpAddr::V6(addr), &mut buf).err(), Some(Error::BufferTooSmall));
+}
+
+#[test]
+fn test_records() {
+  let mut name_buf = [0; 32];
+  let name = Name::parse_str("example.com", &mut name_buf).expect("parsing name failed");
+  let mut name_server_buf = [0; 32];
+  let name_server = Name::parse_str("ns.example.com", &mut name_server_buf).expect("parsing name failed");
+
+  let mut buf = Message::BUFFER;
+  let message = Message::builder(&mut buf)
+    .header(Header::builder().kind(HeaderKind::Response).build())
+    .question(&Question::new(name.clone(), QueryKind::A, QueryClass::IN)).expect("adding question failed")
+    .additional_record(&Answer::builder().name(name_server.clone()).ttl(300).rdata(&[192, 0, 2, 53]).build()).expect("adding additional record failed")
+    .name_server(&Answer::builder().name(name.clone()).kind(QueryKind::NS).ttl(300).rdata(&[2, b'n', b's', 7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 3, b'c', b'o', b'm', 0]).build()).expect("adding name server failed")
+    .answer(&Answer::builder().name(name).ttl(300).rdata(&[192, 0, 2, 1]).build()).expect("adding answer failed")
+    .build();
+
+  let mut bytes = message.as_bytes().to_vec();
+  let message = Message::parse(&mut bytes).expect("parsing failed");
+
+  assert_eq!(message.header().answer_count(), 1);
+  assert_eq!(message.header().name_server_count(), 1);
+  assert_eq!(message.header().additional_records_count(), 1);
+
+  let mut records = message.records();
+  assert_eq!(records.len(), 3);
+
+  let (section, record) = records.next().expect("`Records` iterator is empty");
+  assert_eq!(section, Section::Answer);
+  assert_eq!(*record.kind(), QueryKind::A);
+
+  let (section, record) = records.next().expect("`Records` iterator is empty");
+  assert_eq!(section, Section::Authority);
+  assert_eq!(record.rdata_name().expect("invalid NS record").to_string(), "ns.example.com");
+
+  let (section, record) = records.next().expect("`Records` iterator is empty");
+  assert_eq!(section, Section::Additional);
+  assert_eq!(record.name(), "ns.example.com");
+
+  assert!(records.next().is_none());
+
+  assert_eq!(message.name_servers().count(), 1);
+  assert_eq!(message.additional_records().count(), 1);
+
+  let output = message.to_string();
+  assert!(output.contains(";; AUTHORITY SECTION:\nexample.com.\t300\tIN\tNS\tns.example.com.\n"));
+  assert!(output.contains(";; ADDITIONAL SECTION:\nns.example.com.\t300\tIN\tA\t192.0.2.53\n"));
+}
+
+#[test]
+fn test_sync_counts() {
+  let mut buf = CAPTIVE_APPLE_COM_AAAA_RESPONSE;
+  let mut response = Message::parse(&mut buf).expect("parsing failed");
+  let expected = response.as_bytes().to_vec();
+
+  unsafe {
+    response.header_mut().set_answer_count(7);
+    response.header_mut().set_name_server_count(1);
+  }
+
+  response.sync_counts();
+  assert_eq!(response.header().question_count(), 1);
+  assert_eq!(response.header().answer_count(), 1);
+  assert_eq!(response.header().name_server_count(), 0);
+  assert_eq!(response.header().additional_records_count(), 0);
+  assert_eq!(response.as_bytes(), &expected[..]);
+
+  // A missing answer is recovered as an additional record.
+  unsafe { response.header_mut().set_answer_count(0) };
+  response.sync_counts();
+  assert_eq!(response.header().answer_count(), 0);
+  assert_eq!(response.header().additional_records_count(), 1);
+  assert_eq!(response.additional_records().count(), 1);
+}
+
+#[test]
+fn test_parse_rdata() {
+  let mut buf = CAPTIVE_APPLE_COM_AAAA_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+  assert!(matches!(answer.parse_rdata(), Rdata::Aaaa(addr) if Some(addr) == answer.as_ipv6()));
+
+  let mut buf = WWW_APPLE_COM_CNAME_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+  match answer.parse_rdata() {
+    Rdata::Cname(name) => assert_eq!(&name, "captive.apple.com"),
+    rdata => panic!("unexpected RDATA: {:?}", rdata),
+  }
+
+  let mut buf = APPLE_COM_MX_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+  assert!(matches!(answer.parse_rdata(), Rdata::Mx { preference: 20, .. }));
+
+  let mut buf = EXAMPLE_COM_SOA_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+  assert!(matches!(answer.parse_rdata(), Rdata::Soa(_)));
+
+  let answer = Answer::builder().kind(QueryKind::A).rdata(&[192, 0, 2, 1, 0]).build();
+  assert!(matches!(answer.parse_rdata(), Rdata::Unknown(&[192, 0, 2, 1, 0])));
+
+  let answer = Answer::builder().kind(QueryKind::NULL).rdata(&[1, 2, 3]).build();
+  assert!(matches!(answer.parse_rdata(), Rdata::Unknown(&[1, 2, 3])));
+}
+
+#[test]
+fn test_parse_strict() {
+  let mut buf = CAPTIVE_APPLE_COM_QUERY;
+  assert!(Message::parse_strict(&mut buf[..35]).is_ok());
+
+  let mut buf = SIP_TCP_EXAMPLE_COM_SRV_RESPONSE;
+  assert!(Message::parse_strict(&mut buf).is_ok());
+
+  // Only the first label may be a wildcard.
+  let mut buf = Message::BUFFER;
+  let query = Message::query("*.example.com", QueryKind::A, &mut buf).expect("creating query failed");
+  let mut bytes = query.to_vec();
+  assert!(Message::parse_strict(&mut bytes).is_ok());
+
+  let mut buf = Message::BUFFER;
+  let query = Message::query("www.*.example.com", QueryKind::A, &mut buf).expect("creating query failed");
+  let mut bytes = query.to_vec();
+  assert_eq!(Message::parse_strict(&mut bytes).err(), Some(Error::InvalidLabel { offset: 16 }));
+
+  // Replace the `t` in `captive` with a control character.
+  let mut buf = CAPTIVE_APPLE_COM_QUERY;
+  buf[16] = 0x07;
+  assert_eq!(Message::parse_strict(&mut buf[..35]).err(), Some(Error::InvalidLabel { offset: 12 }));
+
+  let request = Message::parse(&mut buf).expect("parsing failed");
+  let question = request.questions().next().expect("`Questions` iterator is empty");
+  assert_eq!(question.name().labels_str().next(), Some("cap\u{7}ive"));
+
+  // Use the reserved label type `0b01` for `captive`.
+  let mut buf = CAPTIVE_APPLE_COM_QUERY;
+  buf[12] |= 0b01000000;
+  assert_eq!(Message::parse_strict(&mut buf[..35]).err(), Some(Error::InvalidLabel { offset: 12 }));
+
+  let request = Message::parse(&mut buf).expect("parsing failed");
+  let question = request.questions().next().expect("`Questions` iterator is empty");
+  assert_eq!(question.name(), "captive.apple.com");
+
+  // Invalid UTF-8 is replaced.
+  let mut buf = CAPTIVE_APPLE_COM_QUERY;
+  buf[16] = 0xFF;
+  assert!(Message::parse_strict(&mut buf).is_err());
+
+  let request = Message::parse(&mut buf).expect("parsing failed");
+  let question = request.questions().next().expect("`Questions` iterator is empty");
+  assert_eq!(question.name().labels_str().collect::<Vec<_>>(), ["\u{FFFD}", "apple", "com"]);
+}
+
+#[test]
+fn test_parse_strict_uncompressed_question_names() {
+  let options = StrictOptions::new().uncompressed_question_names(true);
+
+  let mut buf = CAPTIVE_APPLE_COM_QUERY;
+  assert!(Message::parse_strict_with(&mut buf[..35], options).is_ok());
+
+  // The second question name is compressed using a pointer to the first one.
+  let mut name_buf = [0; 32];
+  let name = Name::parse_str("apple.com", &mut name_buf).expect("parsing name failed");
+  parse!(query);
+  let mut query_buf = Message::BUFFER;
+  let mut message = Message::builder(&mut query_buf).build();
+  message.add_question(&query.questions().next().expect("`Questions` iterator is empty")).expect("adding question failed");
+  message.add_question(&Question::new(name, QueryKind::A, QueryClass::IN)).expect("adding question failed");
+  assert_eq!(message.len(), 41);
+
+  let mut bytes = message.to_vec();
+  assert!(Message::parse_strict(&mut bytes).is_ok());
+  assert!(Message::parse_strict_with(&mut bytes, StrictOptions::default()).is_ok());
+  assert_eq!(Message::parse_strict_with(&mut bytes, options).err(), Some(Error::Pointer { offset: 35 }));
+}
+
+#[test]
+fn test_parse_strict_trailing_bytes() {
+  let mut buf = CAPTIVE_APPLE_COM_QUERY;
+  assert_eq!(Message::parse_strict(&mut buf).err(), Some(Error::TrailingBytes { offset: 35 }));
+  assert!(Message::parse(&mut buf).is_ok());
+
+  let mut buf = CAPTIVE_APPLE_COM_AAAA_RESPONSE;
+  assert!(Message::parse_strict(&mut buf).is_ok());
+  assert_eq!(Message::parse_strict(&mut buf[..62]).err(), Some(Error::MessageTooShort { offset: 45 }));
+}
+
+#[test]
+fn test_parse_strict_rdata_len() {
+  parse!(query);
+  let question = query.questions().next().expect("`Questions` iterator is empty");
+
+  let mut buf = Message::BUFFER;
+  let mut response = Message::builder(&mut buf).build();
+  response.add_question(&question).expect("adding question failed");
+  response.add_answer(&Answer::builder().name(question.name().clone()).rdata(&[192, 0, 2, 1, 0]).build()).expect("adding answer failed");
+
+  let mut bytes = response.to_vec();
+  assert!(Message::parse(&mut bytes).is_ok());
+  assert_eq!(Message::parse_strict(&mut bytes).err(), Some(Error::InvalidRdata { offset: 47 }));
+
+  // An `AAAA` record with an `A` kind.
+  let mut buf = CAPTIVE_APPLE_COM_AAAA_RESPONSE;
+  buf[38] = 1;
+  assert_eq!(Message::parse_strict(&mut buf).err(), Some(Error::InvalidRdata { offset: 47 }));
+
+  assert!(Answer::builder().kind(QueryKind::AAAA).rdata(&[0; 16]).build().validate().is_ok());
+  assert!(Answer::builder().kind(QueryKind::AAAA).rdata(&[0; 4]).build().validate().is_err());
+}
+
+#[test]
+fn test_name_display_escaped() {
+  let mut buf = [
+    0, 1,                                        // ID
+    1, 0,                                        // Kind
+    0, 1,                                        // Question Count
+    0, 0,                                        // Answer Count
+    0, 0,                                        // Name Server Count
+    0, 0,                                        // Additional Records Count
+    3, b'a', b'.', b'b',                         // Label "a.b"
+    3, b'c', b'\\', b' ',                         // Label "c\\ "
+    7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', // Label "example"
+    3, b'c', b'o', b'm',                         // Label "com"
+    0,                                           // Label End
+    0, 1,                                        // Question Kind
+    0, 1,                                        // Question Class
+  ];
+  let query = Message::parse(&mut buf).expect("parsing failed");
+  let question = query.questions().next().expect("`Questions` iterator is empty");
+  let name = question.name();
+
+  assert_eq!(name.label_count(), 4);
+  assert_eq!(name.to_string(), "a\\.b.c\\\\\\032.example.com");
+  assert_eq!(format!("{:#}", name), "a\\.b.c\\\\\\032.example.com.");
+}
+
+#[test]
+fn test_name_parse_str_escaped() {
+  let mut name_buf = [0; 32];
+  let name = Name::parse_str("a\\.b.example.com", &mut name_buf).expect("parsing name failed");
+  assert_eq!(name.labels_str().collect::<Vec<_>>(), ["a.b", "example", "com"]);
+  assert_eq!(name.to_string(), "a\\.b.example.com");
+
+  let mut name_buf = [0; 32];
+  let name = Name::parse_str("c\\\\\\032\\255.example.com.", &mut name_buf).expect("parsing name failed");
+  assert_eq!(name.to_string(), "c\\\\\\032\\255.example.com");
+  assert_eq!(&name_buf[..5], &[4, b'c', b'\\', b' ', 255]);
+
+  // An escaped trailing dot belongs to the label.
+  let mut name_buf = [0; 32];
+  let name = Name::parse_str("com\\.", &mut name_buf).expect("parsing name failed");
+  assert_eq!(name.labels_str().collect::<Vec<_>>(), ["com."]);
+
+  let mut name_buf = [0; 32];
+  assert_eq!(Name::parse_str("a\\25.com", &mut name_buf).err(), Some(Error::InvalidLabel { offset: 1 }));
+  assert_eq!(Name::parse_str("a\\256.com", &mut name_buf).err(), Some(Error::InvalidLabel { offset: 1 }));
+  assert_eq!(Name::parse_str("a\\", &mut name_buf).err(), Some(Error::InvalidLabel { offset: 1 }));
+}
+
+#[test]
+fn test_name_write_canonical() {
+  let mut name_buf = [0; 32];
+  let name = Name::parse_str("CAPTIVE.Apple.com", &mut name_buf).expect("parsing name failed");
+
+  let mut out = [0; 32];
+  let len = name.write_canonical(&mut out).expect("writing name failed");
+  assert_eq!(&out[..len], &CAPTIVE_APPLE_COM_QUERY[12..31]);
+
+  // Compressed names are written uncompressed.
+  let mut buf = WWW_APPLE_COM_CNAME_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+  let answer = response.answers().next().expect("`Answers` iterator is empty");
+  let len = answer.rdata_name().expect("invalid CNAME record").write_canonical(&mut out).expect("writing name failed");
+  assert_eq!(&out[..len], &CAPTIVE_APPLE_COM_QUERY[12..31]);
+
+  assert_eq!(name.write_canonical(&mut out[..18]), Err(Error::BufferTooSmall));
+}
+
+#[test]
+fn test_name_ordering() {
+  let names = [
+    "example.com",
+    "a.example.com",
+    "yljkjljk.a.example.com",
+    "Z.a.example.com",
+    "zABC.a.EXAMPLE.com",
+    "z.example.com",
+    "\u{1}.z.example.com",
+    "*.z.example.com",
+  ];
+
+  let mut bufs = [[0; 32]; 8];
+  let mut parsed = Vec::new();
+  for (name, buf) in names.iter().zip(bufs.iter_mut()) {
+    parsed.push(Name::parse_str(name, buf).expect("parsing name failed"));
+  }
+
+  let mut sorted = parsed.clone();
+  sorted.reverse();
+  sorted.sort();
+
+  assert_eq!(sorted, parsed);
+
+  let mut name_buf = [0; 32];
+  let uppercase = Name::parse_str("A.EXAMPLE.COM", &mut name_buf).expect("parsing name failed");
+  assert_eq!(uppercase.cmp(&parsed[1]), core::cmp::Ordering::Equal);
+  assert!(parsed[0] < parsed[1]);
+  assert!(parsed[1] < parsed[5]);
+}
+
+#[test]
+fn test_name_suffix() {
+  let mut bufs = [[0; 32]; 5];
+  let [buf_1, buf_2, buf_3, buf_4, buf_5] = &mut bufs;
+  let zone = Name::parse_str("example.com", buf_1).expect("parsing name failed");
+  let subdomain = Name::parse_str("www.EXAMPLE.com", buf_2).expect("parsing name failed");
+  let sibling = Name::parse_str("notexample.com", buf_3).expect("parsing name failed");
+  let root = Name::parse_str(".", buf_4).expect("parsing name failed");
+  let com = Name::parse_str("com", buf_5).expect("parsing name failed");
+
+  assert!(subdomain.ends_with(&zone));
+  assert!(subdomain.is_subdomain_of(&zone));
+  assert!(zone.ends_with(&zone));
+  assert!(!zone.is_subdomain_of(&zone));
+  assert!(!sibling.ends_with(&zone));
+  assert!(!sibling.is_subdomain_of(&zone));
+  assert!(!zone.ends_with(&subdomain));
+  assert!(sibling.is_subdomain_of(&com));
+  assert!(zone.is_subdomain_of(&root));
+  assert!(root.ends_with(&root));
+  assert!(!root.is_subdomain_of(&root));
+}
+
+#[test]
+fn test_name_root() {
+  let root = Name::root();
+  assert!(root.is_root());
+  assert_eq!(root.label_count(), 0);
+  assert_eq!(root.byte_len(), 1);
+  assert_eq!(root.to_string(), "");
+  assert_eq!(format!("{:#}", root), ".");
+
+  let mut name_buf = [0; 32];
+  assert!(Name::parse_str(".", &mut name_buf).expect("parsing name failed").is_root());
+
+  let mut name_buf = [0; 32];
+  let name = Name::parse_str("example.com", &mut name_buf).expect("parsing name failed");
+  assert!(!name.is_root());
+  assert_eq!(format!("{:#}", name), "example.com.");
+
+  assert!(Answer::builder().build().name().is_root());
+}
+
+#[test]
+fn test_clone_records() {
+  let mut buf = CAPTIVE_APPLE_COM_AAAA_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+
+  let question = response.questions().next().expect("`Questions` iterator is empty").clone();
+  let answers = response.answers().collect::<Vec<_>>();
+  let answer = answers[0].clone();
+
+  assert_eq!(answer.name(), answers[0].name());
+  assert_eq!(answer.kind(), answers[0].kind());
+  assert_eq!(answer.rdata(), answers[0].rdata());
+  assert_eq!(answer.as_ipv6(), answers[0].as_ipv6());
+
+  let mut buf = Message::BUFFER;
+  let mut copy = Message::builder(&mut buf).header(response.header().clone()).build();
+  copy.clear();
+  copy.add_question(&question).expect("adding question failed");
+  copy.add_answer(&answer).expect("adding answer failed");
+
+  assert!(copy.eq_ignoring_id(&response));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_message_to_vec() {
+  parse!(request);
+
+  let bytes: Vec<u8> = request.to_vec();
+  assert_eq!(bytes, &CAPTIVE_APPLE_COM_QUERY[..35]);
+
+  let request = Message::parse_ref(&CAPTIVE_APPLE_COM_QUERY).expect("parsing failed");
+  assert_eq!(request.to_vec(), bytes);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_message_write_to_vec() {
+  parse!(request);
+
+  let mut bytes = Vec::new();
+  request.write_to(&mut bytes).expect("writing failed");
+  assert_eq!(bytes, request.as_bytes());
+}
+
+#[test]
+fn test_message_write_to_slice() {
+  parse!(request);
+
+  let mut buf = [0; 40];
+  let mut out = &mut buf[..];
+  request.write_to(&mut out).expect("writing failed");
+  assert_eq!(out.len(), 5);
+  assert_eq!(&buf[..35], request.as_bytes());
+
+  let mut buf = [0; 20];
+  assert!(request.write_to(&mut &mut buf[..]).is_err());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_error_into_io_error() {
+  fn parse(buf: &mut [u8]) -> std::io::Result<u16> {
+    Ok(Message::parse(buf)?.header().id())
+  }
+
+  let mut buf = CAPTIVE_APPLE_COM_QUERY;
+  assert_eq!(parse(&mut buf).expect("parsing failed"), 7932);
+
+  let err = parse(&mut buf[..20]).expect_err("parsing succeeded");
+  assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+  assert_eq!(err.to_string(), "message too short at offset 20");
+
+  let err = std::io::Error::from(Error::BufferTooSmall);
+  assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_extended_response_code() {
+  let header = Header::builder().kind(HeaderKind::Response).response_code(ResponseCode::FormatError).build();
+
+  let mut buf = Message::BUFFER;
+  let message = Message::builder(&mut buf).header(header.clone()).build();
+  assert_eq!(message.extended_response_code(), ResponseCode::FormatError);
+
+  let opt = Answer::builder()
+    .kind(QueryKind::OPT)
+    .class(QueryClass::from(4096))
+    .ttl(0x01_00_00_00)
+    .build();
+
+  let mut buf = Message::BUFFER;
+  let message = Message::builder(&mut buf)
+    .header(header)
+    .additional_record(&opt).expect("adding additional record failed")
+    .build();
+
+  assert_eq!(message.header().response_code(), ResponseCode::FormatError);
+  assert_eq!(message.extended_response_code(), ResponseCode::BadKey);
+  assert_eq!(QueryKind::from(41), QueryKind::OPT);
+  assert_eq!(QueryKind::OPT.to_string(), "OPT");
+}
+
+#[test]
+fn test_set_opcode_reserved() {
+  let mut buf = CAPTIVE_APPLE_COM_QUERY;
+  // Response with authoritative answer and recursion desired.
+  buf[2] = 0b10000101;
+  let mut message = Message::parse(&mut buf).expect("parsing failed");
+
+  message.header_mut().set_opcode(OpCode::Reserved(15));
+  assert_eq!(message.header().opcode(), OpCode::Reserved(15));
+  assert_eq!(message.header().kind(), HeaderKind::Response);
+  assert!(message.header().authoritative_answer());
+  assert!(message.header().recursion_desired());
+
+  message.header_mut().set_opcode(OpCode::Reserved(0xFF));
+  assert_eq!(message.header().opcode(), OpCode::Reserved(15));
+  assert_eq!(message.header().kind(), HeaderKind::Response);
+  assert!(message.header().authoritative_answer());
+  assert!(message.header().recursion_desired());
+
+  message.header_mut().set_opcode(OpCode::Query);
+  assert_eq!(message.header().opcode(), OpCode::Query);
+  assert_eq!(message.as_bytes()[2], 0b10000101);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "extended response code must be set in the OPT record")]
+fn test_set_response_code_extended() {
+  let mut buf = CAPTIVE_APPLE_COM_QUERY;
+  let mut message = Message::parse(&mut buf).expect("parsing failed");
+  message.header_mut().set_response_code(ResponseCode::BadKey);
+}
+
+const EXAMPLE_COM_NSID_RESPONSE: [u8; 47] = [
+  30, 252,                                     // ID
+  129, 128,                                    // Kind
+  0, 1,                                        // Question Count
+  0, 0,                                        // Answer Count
+  0, 0,                                        // Name Server Count
+  0, 1,                                        // Additional Records Count
+  7, b'e', b'x', b'a', b'm', b'p', b'l', b'e', // Label "example"
+  3, b'c', b'o', b'm',                         // Label "com"
+  0,                                           // Label End
+  0, 1,                                        // Question Kind
+  0, 1,                                        // Question Class
+  0,                                           // Root Name
+  0, 41,                                       // Additional Record Kind
+  16, 0,                                       // UDP Payload Size
+  0, 0, 128, 0,                                // Extended Response Code, Version and Flags
+  0, 7,                                        // RDATA Length
+  0, 3,                                        // Option Code "NSID"
+  0, 3,                                        // Option Length
+  b'n', b's', b'1',                            // Option Data
+];
+
+#[test]
+fn test_answer_as_opt() {
+  let mut buf = EXAMPLE_COM_NSID_RESPONSE;
+  let response = Message::parse(&mut buf).expect("parsing failed");
+
+  let record = response.additional_records().next().expect("`Answers` iterator is empty");
+  assert_eq!(*record.kind(), QueryKind::OPT);
+
+  let opt = record.as_opt().expect("invalid OPT record");
+  assert_eq!(opt.udp_payload_size(), 4096);
+  assert_eq!(opt.extended_response_code(), 0);
+  assert_eq!(opt.version(), 0);
+  assert!(opt.dnssec_ok());
+
+  let mut options = opt.options();
+  assert_eq!(options.next(), Some((3, &b"ns1"[..])));
+  assert_eq!(options.next(), None);
+
+  assert!(matches!(record.parse_rdata(), Rdata::Opt(_)));
+
+  // A truncated option ends the iteration.
+  let rdata = [0, 10, 0, 2, 1, 2, 0, 3, 0, 5, b'a'];
+  let record = Answer::builder().kind(QueryKind::OPT).rdata(&rdata).build();
+  let mut options = record.as_opt().expect("invalid OPT record").options();
+  assert_eq!(options.next(), Some((10, &[1, 2][..])));
+  assert_eq!(options.next(), None);
+  assert_eq!(options.next(), None);
+
+  assert!(Answer::builder().kind(QueryKind::A).build().as_opt().is_none());
+}
+
+#[test]
+fn test_opt_builder_query() {
+  let mut buf = Message::BUFFER;
+  let mut query = Message::query("example.com", QueryKind::A, &mut buf).expect("creating query failed");
+
+  let opt = Opt::builder().payload_size(1232).dnssec_ok(true);
+  query.add_additional_record(&opt.build()).expect("adding OPT record failed");
+
+  let mut bytes = query.as_bytes().to_vec();
+  let query = Message::parse(&mut bytes).expect("parsing failed");
+  assert_eq!(query.header().additional_records_count(), 1);
+
+  let record = query.additional_records().next().expect("`Answers` iterator is empty");
+  assert!(record.name().is_root());
+  assert_eq!(u16::from(*record.kind()), 41);
+  assert_eq!(u16::from(*record.class()), 1232);
+  assert_eq!(record.ttl(), 0x8000);
+  assert!(record.rdata().is_empty());
+
+  let opt = record.as_opt().expect("invalid OPT record");
+  assert_eq!(opt.udp_payload_size(), 1232);
+  assert!(opt.dnssec_ok());
+  assert_eq!(opt.version(), 0);
+}
+
+#[test]
+fn test_opt_cookie() {
+  let client = [1, 2, 3, 4, 5, 6, 7, 8];
+  let server = [9; 16];
+
+  let builder = Opt::builder().payload_size(1232).dnssec_ok(true).cookie(client, Some(&server)).expect("invalid cookie");
+  let record = builder.build();
+  assert!(record.name().is_root());
+  assert_eq!(*record.kind(), QueryKind::OPT);
+  assert_eq!(record.rdata().len(), 2 + 2 + 8 + 16);
+
+  let opt = record.as_opt().expect("invalid OPT record");
+  assert_eq!(opt.udp_payload_size(), 1232);
+  assert!(opt.dnssec_ok());
+
+  let cookie = opt.cookie().expect("missing cookie");
+  assert_eq!(cookie.client(), client);
+  assert_eq!(cookie.server(), Some(&server[..]));
+
+  let builder = Opt::builder().cookie(client, None).expect("invalid cookie");
+  let record = builder.build();
+  let opt = record.as_opt().expect("invalid OPT record");
+  assert_eq!(opt.udp_payload_size(), 512);
+  assert_eq!(opt.cookie().map(|c| c.server()), Some(None));
+
+  assert_eq!(Opt::builder().cookie(client, Some(&[0; 7])).err(), Some(Error::InvalidOption));
+  assert_eq!(Opt::builder().cookie(client, Some(&[0; 33])).err(), Some(Error::InvalidOption));
+
+  // A server cookie with an invalid length is ignored.
+  let rdata = [
+    0, 10, // Option Code
+    0, 12, // Option Length
+    1, 2, 3, 4, 5, 6, 7, 8, // Client Cookie
+    9, 9, 9, 9, // Server Cookie
+  ];
+  let record = Answer::builder().kind(QueryKind::OPT).rdata(&rdata).build();
+  assert_eq!(record.as_opt().expect("invalid OPT record").cookie(), None);
+}
+
+#[test]
+fn test_opt_ecs() {
+  let ecs = EcsOption::new("192.0.2.123".parse().unwrap(), 24, 0);
+  assert_eq!(ecs.family(), 1);
+  assert_eq!(ecs.address(), "192.0.2.0".parse::<std::net::IpAddr>().unwrap());
+
+  let builder = Opt::builder().ecs(&ecs).expect("invalid client subnet");
+  let record = builder.build();
+  assert_eq!(record.rdata(), &[
+    0, 8, // Option Code
+    0, 7, // Option Length
+    0, 1, // Family
+    24, // Source Prefix Length
+    0, // Scope Prefix Length
+    192, 0, 2, // Address
+  ]);
+
+  let opt = record.as_opt().expect("invalid OPT record");
+  assert_eq!(opt.ecs(), Some(ecs));
+
+  let ecs = EcsOption::new("2001:db8::1".parse().unwrap(), 56, 48);
+  let builder = Opt::builder().ecs(&ecs).expect("invalid client subnet");
+  let record = builder.build();
+  assert_eq!(record.rdata().len(), 2 + 2 + 4 + 7);
+  assert_eq!(record.as_opt().expect("invalid OPT record").ecs(), Some(ecs));
+
+  // The address length must match the source prefix length.
+  let rdata = [0, 8, 0, 8, 0, 1, 24, 0, 192, 0, 2, 1];
+  let record = Answer::builder().kind(QueryKind::OPT).rdata(&rdata).build();
+  assert_eq!(record.as_opt().expect("invalid OPT record").ecs(), None);
+}
+
+#[test]
+fn test_wire_len() {
+  parse!(query);
+  let question = query.questions().next().expect("`Questions` iterator is empty");
+  // "captive.apple.com" is 19 bytes on the wire, plus kind and class.
+  assert_eq!(question.wire_len(), 19 + 4);
+
+  let answer = Answer::builder()
+    .name(question.name().clone())
+    .rdata(&[192, 0, 2, 1])
+    .build();
+  assert_eq!(answer.wire_len(), 19 + 10 + 4);
+
+  let mut buf = Message::BUFFER;
+  let mut message = Message::builder(&mut buf).build();
+  assert_eq!(message.remaining(), Message::BUFFER.len() - 12);
+
+  message.add_question(&question).expect("adding question failed");
+  assert_eq!(message.remaining(), Message::BUFFER.len() - 12 - question.wire_len());
+
+  // The answer name is compressed, so less than `wire_len` is used.
+  let remaining = message.remaining();
+  message.add_answer(&answer).expect("adding answer failed");
+  assert_eq!(message.remaining(), remaining - (2 + 10 + 4));
+  assert!(remaining - message.remaining() < answer.wire_len());
+}